@@ -1,15 +1,23 @@
 #![allow(deprecated)]
 
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+use anchor_lang::solana_program::{ed25519_program, pubkey, sysvar};
 use anchor_lang::system_program;
+use anchor_lang::Discriminator;
 use anchor_spl::{
-    associated_token::AssociatedToken,
+    associated_token::{self, AssociatedToken},
     token::{self, Mint, Token, TokenAccount},
     metadata::{
         create_metadata_accounts_v3, CreateMetadataAccountsV3,
         create_master_edition_v3, CreateMasterEditionV3,
         set_and_verify_collection, SetAndVerifyCollection,
-        Metadata,
+        burn_nft, BurnNft,
+        freeze_delegated_account, FreezeDelegatedAccount,
+        thaw_delegated_account, ThawDelegatedAccount,
+        Metadata, MetadataAccount,
     },
 };
 
@@ -33,59 +41,174 @@ pub mod vigri_nft_presale_minter {
         global_config.payment_mint = args.payment_mint;
 
         global_config.is_sales_paused = false;
-        global_config.tiers = GlobalConfig::default_tiers();
+        global_config.version = GLOBAL_CONFIG_VERSION;
+        let config_delay_seconds = args
+            .config_delay_seconds
+            .unwrap_or(DEFAULT_CONFIG_DELAY_SECONDS);
+        require!(
+            (0..=MAX_CONFIG_DELAY_SECONDS).contains(&config_delay_seconds),
+            PresaleError::InvalidConfigDelay
+        );
+        global_config.config_delay_seconds = config_delay_seconds;
+
+        emit!(ConfigInitialized {
+            admin: global_config.admin,
+            collection_mint: global_config.collection_mint,
+            payment_mint: global_config.payment_mint,
+            version: global_config.version,
+            config_delay_seconds: global_config.config_delay_seconds,
+        });
 
         Ok(())
     }
 
     // -----------------------------------------
-    // 2) Admin config update (prices, flags, pause)
+    // 2) Admin config update (pause is immediate, tier changes are timelocked)
     // -----------------------------------------
     pub fn update_config(ctx: Context<UpdateConfig>, args: UpdateConfigArgs) -> Result<()> {
         let global_config = &mut ctx.accounts.global_config;
+        let authority = ctx.accounts.authority.key();
 
-        // Extra safety, even though we also have `has_one = admin` in accounts
-        require_keys_eq!(
-            global_config.admin,
-            ctx.accounts.admin.key(),
-            PresaleError::Unauthorized
-        );
-
-        // 1) Global pause flag
+        // 1) Global pause flag (pauser)
         if let Some(paused) = args.is_sales_paused {
+            require!(
+                global_config.has_role(Role::Pauser, authority),
+                PresaleError::Unauthorized
+            );
             global_config.is_sales_paused = paused;
+
+            if paused {
+                emit!(SalesPaused { authority });
+            } else {
+                emit!(SalesResumed { authority });
+            }
         }
 
-        // 2) Per-tier updates (optional)
-        if let Some(tier_id) = args.tier_id {
-            let idx = tier_id as usize;
-            require!(idx < global_config.tiers.len(), PresaleError::InvalidTierId);
+        // 2) Per-tier updates (optional, only when a tier account is passed)
+        let has_tier_updates = args.new_price_lamports.is_some()
+            || args.new_kyc_required.is_some()
+            || args.new_invite_only.is_some()
+            || args.new_transferable.is_some()
+            || args.new_admin_quota.is_some()
+            || args.new_supply_total.is_some()
+            || args.new_stake_weight.is_some();
+
+        if let Some(tier) = ctx.accounts.tier.as_mut() {
+            // Tier pause (pauser, immediate)
+            if let Some(paused) = args.tier_paused {
+                require!(
+                    global_config.has_role(Role::Pauser, authority),
+                    PresaleError::Unauthorized
+                );
+                tier.is_paused = paused;
+
+                if paused {
+                    emit!(TierSalesPaused {
+                        tier_id: tier.id,
+                        authority,
+                    });
+                } else {
+                    emit!(TierSalesResumed {
+                        tier_id: tier.id,
+                        authority,
+                    });
+                }
+            }
 
-            let tier = &mut global_config.tiers[idx];
+            // Price (price manager); with a multisig it goes through SetTierPrice
+            if args.new_price_lamports.is_some() {
+                require!(
+                    global_config.has_role(Role::PriceManager, authority),
+                    PresaleError::Unauthorized
+                );
+                require!(!global_config.multisig.is_active(), PresaleError::MultisigRequired);
+            }
+            // KYC gating (KYC manager)
+            if args.new_kyc_required.is_some() {
+                require!(
+                    global_config.has_role(Role::KycManager, authority),
+                    PresaleError::Unauthorized
+                );
+            }
+            // Remaining flags stay admin-only
+            if args.new_invite_only.is_some()
+                || args.new_transferable.is_some()
+                || args.new_admin_quota.is_some()
+                || args.new_supply_total.is_some()
+                || args.new_stake_weight.is_some()
+                || args.lock_supply
+            {
+                require_keys_eq!(global_config.admin, authority, PresaleError::Unauthorized);
+            }
+            // admin_quota must fit the supply this change leaves behind
+            if args.new_admin_quota.is_some() || args.new_supply_total.is_some() {
+                let admin_quota = args.new_admin_quota.unwrap_or(tier.admin_quota);
+                let supply_total = args.new_supply_total.unwrap_or(tier.supply_total);
+                require!(
+                    admin_quota <= supply_total,
+                    PresaleError::InvalidTierConfig
+                );
+            }
 
-            if let Some(price) = args.new_price_lamports {
-                tier.price_lamports = price;
+            // Supply lock only restricts the admin, so it does not wait for the timelock
+            if args.lock_supply && !tier.supply_locked {
+                tier.supply_locked = true;
+                tier.emit_flags_changed();
             }
-            if let Some(kyc) = args.new_kyc_required {
-                tier.kyc_required = kyc;
+
+            // Anti-bot mode is a sale-protection switch, applied immediately
+            if let Some(max_mints) = args.new_anti_bot_max_mints {
+                require_keys_eq!(global_config.admin, authority, PresaleError::Unauthorized);
+                tier.anti_bot_max_mints = max_mints;
+                tier.emit_flags_changed();
             }
-            if let Some(invite_only) = args.new_invite_only {
-                tier.invite_only = invite_only;
+            if let Some(supply_total) = args.new_supply_total {
+                require!(
+                    supply_total >= tier.supply_minted && supply_total <= tier.supply_max,
+                    PresaleError::InvalidSupply
+                );
+                require!(
+                    !tier.supply_locked || supply_total <= tier.supply_total,
+                    PresaleError::SupplyLocked
+                );
             }
-            if let Some(transferable) = args.new_transferable {
-                tier.transferable = transferable;
+
+            // Queue the change, merged with whatever is still pending for this tier
+            if has_tier_updates {
+                tier.queue_change(TierChange {
+                    price_lamports: args.new_price_lamports,
+                    kyc_required: args.new_kyc_required,
+                    invite_only: args.new_invite_only,
+                    transferable: args.new_transferable,
+                    admin_quota: args.new_admin_quota,
+                    supply_total: args.new_supply_total,
+                    stake_weight: args.new_stake_weight,
+                    effective_at: global_config.resolve_effective_at(args.effective_at)?,
+                });
             }
+        } else {
+            require!(
+                !has_tier_updates
+                    && !args.lock_supply
+                    && args.tier_paused.is_none()
+                    && args.new_anti_bot_max_mints.is_none(),
+                PresaleError::InvalidTierId
+            );
         }
 
         Ok(())
     }
 
     // -----------------------------------------
-    // 3) Admin: update collection_mint in GlobalConfig
+    // 3) Admin: queue a collection_mint change in GlobalConfig (timelocked)
     // -----------------------------------------
     pub fn update_collection_mint(ctx: Context<UpdateCollectionMint>) -> Result<()> {
         let global_config = &mut ctx.accounts.global_config;
-        global_config.collection_mint = ctx.accounts.new_collection_mint.key();
+
+        // With a multisig configured this goes through propose/approve/execute
+        require!(!global_config.multisig.is_active(), PresaleError::MultisigRequired);
+
+        global_config.queue_collection_mint(ctx.accounts.new_collection_mint.key(), None)?;
         Ok(())
     }
 
@@ -93,154 +216,134 @@ pub mod vigri_nft_presale_minter {
     // 4) Public mint for regular tiers
     // -----------------------------------------
     pub fn mint_nft(ctx: Context<MintNft>, args: MintNftArgs) -> Result<()> {
-        // Clone AccountInfo before taking a mutable reference
         let global_config_info = ctx.accounts.global_config.to_account_info();
-        let global_config = &mut ctx.accounts.global_config;
+        let global_config = &ctx.accounts.global_config;
 
-        // 1) Check global sales pause
+        // 1) Check global sales pause (per-tier pause is checked below)
         require!(!global_config.is_sales_paused, PresaleError::SalesPaused);
 
-        // 2) Resolve tier by index
-        let idx = args.tier_id as usize;
-        require!(idx < global_config.tiers.len(), PresaleError::InvalidTierId);
+        // 2) Tier is resolved by its PDA (validated in accounts);
+        //    a due timelocked change is applied before pricing the mint
+        let tier = &mut ctx.accounts.tier;
+        if let Some(change) = tier.apply_pending_if_due(Clock::get()?.unix_timestamp) {
+            emit!(TierChangeApplied {
+                tier_id: tier.id,
+                change,
+            });
+        }
 
-        let tier = &mut global_config.tiers[idx];
+        // 3) Tier pause + anti-bot + supply checks
+        require!(!tier.is_paused, PresaleError::TierPaused);
+        if tier.anti_bot_max_mints > 0 {
+            let instructions = ctx
+                .accounts
+                .instructions
+                .as_ref()
+                .ok_or(error!(PresaleError::AntiBotRejected))?;
+            check_anti_bot(instructions, tier.anti_bot_max_mints)?;
+        }
 
-        // 3) Supply checks
+        // 3a) Slot-window throttles (global and per tier), when enabled
+        let slot = Clock::get()?.slot;
+        if global_config.rate_limited {
+            ctx.accounts
+                .global_rate_limiter
+                .as_mut()
+                .ok_or(error!(PresaleError::MissingRateLimiter))?
+                .record_mint(slot)?;
+        }
+        if tier.rate_limited {
+            ctx.accounts
+                .tier_rate_limiter
+                .as_mut()
+                .ok_or(error!(PresaleError::MissingRateLimiter))?
+                .record_mint(slot)?;
+        }
         require!(tier.supply_minted < tier.supply_total, PresaleError::TierSoldOut);
         require!(tier.price_lamports > 0, PresaleError::TierPriceNotSet);
 
-        // 4) KYC / invite flags (proofs are opaque blobs for now)
+        // 4) KYC (registry entry of the recipient) / invite flags
         if tier.kyc_required {
-            require!(args.kyc_proof.is_some(), PresaleError::KycRequired);
+            check_kyc(ctx.accounts.kyc_record.as_deref(), Clock::get()?.unix_timestamp)?;
         }
 
         if tier.invite_only {
             require!(args.invite_proof.is_some(), PresaleError::InviteRequired);
         }
 
-        // 5) Payment in lamports: payer -> admin
+        // 5) Payment in lamports: payer -> treasury PDA
         let cpi_ctx_transfer = CpiContext::new(
             ctx.accounts.system_program.to_account_info(),
             system_program::Transfer {
                 from: ctx.accounts.payer.to_account_info(),
-                to: ctx.accounts.admin.to_account_info(),
+                to: ctx.accounts.treasury.to_account_info(),
             },
         );
         system_program::transfer(cpi_ctx_transfer, tier.price_lamports)?;
 
-        // 6) Mint 1 token (NFT) to payer's associated token account
+        // 6) Mint 1 token (NFT) to the recipient's associated token account
         let cpi_ctx_mint = CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
             token::MintTo {
                 mint: ctx.accounts.mint.to_account_info(),
-                to: ctx.accounts.payer_token_account.to_account_info(),
+                to: ctx.accounts.recipient_token_account.to_account_info(),
                 authority: ctx.accounts.payer.to_account_info(),
             },
         );
         token::mint_to(cpi_ctx_mint, 1)?;
 
-        // 7) Prepare signer seeds for PDA (GlobalConfig as update_authority / collection_authority)
-        let bump = ctx.bumps.global_config;
-        let signer_seeds: &[&[u8]] = &[GLOBAL_CONFIG_SEED, &[bump]];
-        let signer: &[&[&[u8]]] = &[signer_seeds];
-
-        // Serial inside tier: minted + 1 (before increment)
-        let global_config = &mut ctx.accounts.global_config;
-        let tier_idx = args.tier_id as usize;
+        // 7) Serial inside tier: minted + 1 (before increment)
         let collection_mint_key = global_config.collection_mint;
-        let tier = &mut global_config.tiers[tier_idx];
+        let tier = &mut ctx.accounts.tier;
         let serial: u16 = tier.supply_minted + 1;
 
-        let design_key = resolve_design_key(args.tier_id, serial, args.design_choice)?;
-        emit!(NftMinted {
-            tier_id: args.tier_id,
+        let design_key = resolve_design_key(tier, serial, args.design_choice)?;
+        let data = presale_nft_data(tier, serial, args.design_choice, ctx.accounts.admin.key())?;
+        let clock = Clock::get()?;
+        emit_cpi!(NftMinted {
+            tier_id: tier.id,
             serial,
             design_key,
             mint: ctx.accounts.mint.key(),
+            recipient: ctx.accounts.recipient.key(),
+            buyer: ctx.accounts.payer.key(),
+            price_paid: tier.price_lamports,
+            payment_mint: NATIVE_SOL_MINT,
+            mint_path: MintPath::Public,
+            uri: data.uri.clone(),
+            slot: clock.slot,
+            unix_timestamp: clock.unix_timestamp,
         });
 
-        // On-chain name shown by wallets (must be short enough for Metaplex constraints)
-        let onchain_name = build_name(args.tier_id, serial, args.design_choice)?;
-
-        // DataV2: collection will be set via set_and_verify_collection
-        let data = DataV2 {
-            name: onchain_name,
-            symbol: PLACEHOLDER_SYMBOL.to_string(),
-            uri: build_uri(args.tier_id, serial, args.design_choice)?,
-            seller_fee_basis_points: 250,
-            creators: Some(vec![Creator {
-                address: ctx.accounts.admin.key(),
-                verified: false,
-                share: 100,
-            }]),
-            collection: None,
-            uses: None,
-        };
-
-        // 7a) Создаём metadata
-        let cpi_ctx = CpiContext::new_with_signer(
-            ctx.accounts.token_metadata_program.to_account_info(),
-            CreateMetadataAccountsV3 {
-                metadata: ctx.accounts.metadata.to_account_info(),
-                mint: ctx.accounts.mint.to_account_info(),
-                mint_authority: ctx.accounts.payer.to_account_info(),
-                update_authority: global_config_info.clone(),
+        // 7a) Metadata + master edition + verified collection (GlobalConfig PDA signs)
+        create_presale_nft_metadata(
+            &NftMetadataAccounts {
                 payer: ctx.accounts.payer.to_account_info(),
-                system_program: ctx.accounts.system_program.to_account_info(),
-                rent: ctx.accounts.rent.to_account_info(),
-            },
-            signer,
-        );
-
-        create_metadata_accounts_v3(
-            cpi_ctx,
-            data,
-            true, // is_mutable
-            true, // update_authority_is_signer (PDA signs via seeds)
-            None, // collection_details
-        )?;
-
-        // 7b) Creating a master edition
-        let cpi_ctx_edition = CpiContext::new_with_signer(
-            ctx.accounts.token_metadata_program.to_account_info(),
-            CreateMasterEditionV3 {
-                edition: ctx.accounts.edition.to_account_info(),
                 mint: ctx.accounts.mint.to_account_info(),
-                update_authority: global_config_info.clone(),
-                mint_authority: ctx.accounts.payer.to_account_info(),
-                payer: ctx.accounts.payer.to_account_info(),
                 metadata: ctx.accounts.metadata.to_account_info(),
+                edition: ctx.accounts.edition.to_account_info(),
+                global_config: global_config_info,
+                collection_mint: ctx.accounts.collection_mint.to_account_info(),
+                collection_metadata: ctx.accounts.collection_metadata.to_account_info(),
+                collection_master_edition: ctx.accounts.collection_master_edition.to_account_info(),
+                token_metadata_program: ctx.accounts.token_metadata_program.to_account_info(),
                 token_program: ctx.accounts.token_program.to_account_info(),
                 system_program: ctx.accounts.system_program.to_account_info(),
                 rent: ctx.accounts.rent.to_account_info(),
             },
-            signer,
-        );
-
-        create_master_edition_v3(cpi_ctx_edition, Some(0))?;
-
-        // 7c) We link the NFT to the collection and immediately verify it (if collection_mint is configured)
-        if collection_mint_key != Pubkey::default() {
-            let cpi_ctx_collection = CpiContext::new_with_signer(
-                ctx.accounts.token_metadata_program.to_account_info(),
-                SetAndVerifyCollection {
-                    metadata: ctx.accounts.metadata.to_account_info(),
-                    collection_authority: global_config_info.clone(),
-                    payer: ctx.accounts.payer.to_account_info(),
-                    update_authority: global_config_info.clone(),
-                    collection_mint: ctx.accounts.collection_mint.to_account_info(),
-                    collection_metadata: ctx.accounts.collection_metadata.to_account_info(),
-                    collection_master_edition: ctx.accounts.collection_master_edition.to_account_info(),
-                },
-                signer,
-            );
-
-            // collection_authority_record = None (there is no separate recording PDA)
-            set_and_verify_collection(cpi_ctx_collection, None)?;
-        }
+            data,
+            ctx.bumps.global_config,
+            collection_mint_key,
+        )?;
 
-        // 8) Updating counters
+        // 8) Per-NFT record + counters
+        ctx.accounts.nft_record.set_inner(NftRecord {
+            mint: ctx.accounts.mint.key(),
+            tier_id: tier.id,
+            serial,
+            bump: ctx.bumps.nft_record,
+            paid_lamports: tier.price_lamports,
+        });
         tier.supply_minted += 1;
 
         Ok(())
@@ -262,513 +365,5880 @@ pub mod vigri_nft_presale_minter {
     pub fn admin_mint(ctx: Context<AdminMint>, args: AdminMintArgs) -> Result<()> {
         // 1) Load global config and resolve tier
         let global_config_info = ctx.accounts.global_config.to_account_info();
-        let global_config = &mut ctx.accounts.global_config;
-
-        let collection_mint_key = global_config.collection_mint;
-
-        let idx = args.tier_id as usize;
-        require!(idx < global_config.tiers.len(), PresaleError::InvalidTierId);
-
-        let tier = &mut global_config.tiers[idx];
-
-        // 2) Supply limits
-        require!(tier.supply_minted < tier.supply_total, PresaleError::TierSoldOut);
+        let collection_mint_key = ctx.accounts.global_config.collection_mint;
 
-        let is_ws20 = tier.id == TierId::Ws20 as u8;
-        if !is_ws20 {
-            // Non-WS20 tiers: max 5% for admin mint
-            let max_admin = tier.supply_total / 20; // 5%
-            if max_admin > 0 {
-                require!(tier.admin_minted < max_admin, PresaleError::TierSoldOut);
-            }
-        }
-        // For WS20 we only enforce total supply limit above.
+        // 2) Supply limits + admin quota
+        check_admin_mint_allowed(&ctx.accounts.tier)?;
 
-        // 3) Mint 1 NFT to admin (treasury = admin)
+        // 3) Mint 1 NFT straight into the recipient's ATA, signed by the minter
         let cpi_ctx_mint = CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
             token::MintTo {
                 mint: ctx.accounts.mint.to_account_info(),
-                to: ctx.accounts.admin_token_account.to_account_info(),
-                authority: ctx.accounts.admin.to_account_info(),
+                to: ctx.accounts.recipient_token_account.to_account_info(),
+                authority: ctx.accounts.authority.to_account_info(),
             },
         );
         token::mint_to(cpi_ctx_mint, 1)?;
 
-        // 4) Prepare signer seeds for PDA (GlobalConfig)
-        let bump = ctx.bumps.global_config;
-        let signer_seeds: &[&[u8]] = &[GLOBAL_CONFIG_SEED, &[bump]];
-        let signer: &[&[&[u8]]] = &[signer_seeds];
-
         // Serial inside tier: minted + 1 (before increment)
-        let global_config = &mut ctx.accounts.global_config;
-        let tier_idx = args.tier_id as usize;
-        let tier = &mut global_config.tiers[tier_idx];
+        let tier = &mut ctx.accounts.tier;
         let serial: u16 = tier.supply_minted + 1;
 
         // Emit event with tier, serial, and computed design key
-        let design_key = resolve_design_key(args.tier_id, serial, args.design_choice)?;
-        emit!(NftMinted {
-            tier_id: args.tier_id,
+        let design_key = resolve_design_key(tier, serial, args.design_choice)?;
+        let data = presale_nft_data(tier, serial, args.design_choice, ctx.accounts.admin.key())?;
+        let clock = Clock::get()?;
+        emit_cpi!(NftMinted {
+            tier_id: tier.id,
             serial,
             design_key,
             mint: ctx.accounts.mint.key(),
+            recipient: ctx.accounts.recipient.key(),
+            buyer: ctx.accounts.authority.key(),
+            price_paid: 0,
+            payment_mint: NATIVE_SOL_MINT,
+            mint_path: MintPath::Admin,
+            uri: data.uri.clone(),
+            slot: clock.slot,
+            unix_timestamp: clock.unix_timestamp,
         });
 
-        // On-chain name shown by wallets (must match public mint)
-        let onchain_name = build_name(args.tier_id, serial, args.design_choice)?;
-
-        let data = DataV2 {
-            name: onchain_name,
-            symbol: PLACEHOLDER_SYMBOL.to_string(),
-            uri: build_uri(args.tier_id, serial, args.design_choice)?,
-            seller_fee_basis_points: 250,
-            creators: Some(vec![Creator {
-                address: ctx.accounts.admin.key(),
-                verified: false,
-                share: 100,
-            }]),
-            collection: None, // will be displayed and verified below
-            uses: None,
-        };
-
-        // 4a) Creating metadata
-        let cpi_ctx = CpiContext::new_with_signer(
-            ctx.accounts.token_metadata_program.to_account_info(),
-            CreateMetadataAccountsV3 {
-                metadata: ctx.accounts.metadata.to_account_info(),
+        // 4) Metadata + master edition + verified collection (must match public mint)
+        create_presale_nft_metadata(
+            &NftMetadataAccounts {
+                payer: ctx.accounts.authority.to_account_info(),
                 mint: ctx.accounts.mint.to_account_info(),
-                mint_authority: ctx.accounts.admin.to_account_info(),
-                update_authority: global_config_info.clone(),
-                payer: ctx.accounts.admin.to_account_info(),
+                metadata: ctx.accounts.metadata.to_account_info(),
+                edition: ctx.accounts.edition.to_account_info(),
+                global_config: global_config_info,
+                collection_mint: ctx.accounts.collection_mint.to_account_info(),
+                collection_metadata: ctx.accounts.collection_metadata.to_account_info(),
+                collection_master_edition: ctx.accounts.collection_master_edition.to_account_info(),
+                token_metadata_program: ctx.accounts.token_metadata_program.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
                 system_program: ctx.accounts.system_program.to_account_info(),
                 rent: ctx.accounts.rent.to_account_info(),
             },
-            signer,
-        );
-
-        create_metadata_accounts_v3(
-            cpi_ctx,
             data,
-            true, // is_mutable
-            true, // update_authority_is_signer (PDA global_config signs)
-            None,
+            ctx.bumps.global_config,
+            collection_mint_key,
         )?;
 
-        // 4b) Master edition
-        let cpi_ctx_edition = CpiContext::new_with_signer(
-            ctx.accounts.token_metadata_program.to_account_info(),
-            CreateMasterEditionV3 {
-                edition: ctx.accounts.edition.to_account_info(),
-                mint: ctx.accounts.mint.to_account_info(),
-                update_authority: global_config_info.clone(),
-                mint_authority: ctx.accounts.admin.to_account_info(),
-                payer: ctx.accounts.admin.to_account_info(),
-                metadata: ctx.accounts.metadata.to_account_info(),
-                token_program: ctx.accounts.token_program.to_account_info(),
-                system_program: ctx.accounts.system_program.to_account_info(),
-                rent: ctx.accounts.rent.to_account_info(),
-            },
-            signer,
+        // 5) Per-NFT record + counters
+        ctx.accounts.nft_record.set_inner(NftRecord {
+            mint: ctx.accounts.mint.key(),
+            tier_id: tier.id,
+            serial,
+            bump: ctx.bumps.nft_record,
+            paid_lamports: 0,
+        });
+        tier.supply_minted += 1;
+        tier.admin_minted += 1;
+
+        Ok(())
+    }
+
+    // -----------------------------------------
+    // 6b) Admin airdrop: admin_mint for N recipients in one transaction
+    // -----------------------------------------
+    // remaining_accounts, AIRDROP_ACCOUNTS_PER_RECIPIENT per recipient:
+    //   [recipient, mint (new keypair, signer), recipient ATA, metadata PDA,
+    //    edition PDA, NftRecord PDA]
+    // Use `airdrop_batches` to split a recipient list into fitting transactions.
+    pub fn admin_airdrop<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AdminAirdrop<'info>>,
+        args: AdminAirdropArgs,
+    ) -> Result<()> {
+        let remaining = ctx.remaining_accounts;
+        require!(
+            !remaining.is_empty()
+                && remaining
+                    .chunks_exact(AIRDROP_ACCOUNTS_PER_RECIPIENT)
+                    .remainder()
+                    .is_empty(),
+            PresaleError::InvalidAirdropAccounts
         );
 
-        create_master_edition_v3(cpi_ctx_edition, Some(0))?;
+        let global_config_info = ctx.accounts.global_config.to_account_info();
+        let collection_mint_key = ctx.accounts.global_config.collection_mint;
+        let authority = ctx.accounts.authority.to_account_info();
+        let clock = Clock::get()?;
+
+        for chunk in remaining.chunks(AIRDROP_ACCOUNTS_PER_RECIPIENT) {
+            let [recipient, mint, recipient_token_account, metadata, edition, nft_record] = chunk
+            else {
+                return err!(PresaleError::InvalidAirdropAccounts);
+            };
 
-        // 4c) Bind to the collection and verify (if collection_mint is configured)
-        if collection_mint_key != Pubkey::default() {
-            let cpi_ctx_collection = CpiContext::new_with_signer(
-                ctx.accounts.token_metadata_program.to_account_info(),
-                SetAndVerifyCollection {
-                    metadata: ctx.accounts.metadata.to_account_info(),
-                    collection_authority: global_config_info.clone(),
-                    payer: ctx.accounts.admin.to_account_info(),
-                    update_authority: global_config_info.clone(),
+            // 1) Same supply + admin quota checks as admin_mint, per NFT
+            check_admin_mint_allowed(&ctx.accounts.tier)?;
+
+            // 2) Create the mint (fresh keypair signs the transaction)
+            require!(mint.is_signer, PresaleError::InvalidAirdropAccounts);
+            let cpi_ctx_create = CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::CreateAccount {
+                    from: authority.clone(),
+                    to: mint.clone(),
+                },
+            );
+            system_program::create_account(
+                cpi_ctx_create,
+                Rent::get()?.minimum_balance(Mint::LEN),
+                Mint::LEN as u64,
+                &ctx.accounts.token_program.key(),
+            )?;
+
+            let cpi_ctx_init = CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::InitializeMint2 {
+                    mint: mint.clone(),
+                },
+            );
+            token::initialize_mint2(cpi_ctx_init, 0, &authority.key(), Some(&authority.key()))?;
+
+            // 3) Create the recipient's ATA (ATA program checks the address)
+            let cpi_ctx_ata = CpiContext::new(
+                ctx.accounts.associated_token_program.to_account_info(),
+                associated_token::Create {
+                    payer: authority.clone(),
+                    associated_token: recipient_token_account.clone(),
+                    authority: recipient.clone(),
+                    mint: mint.clone(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                },
+            );
+            associated_token::create(cpi_ctx_ata)?;
+
+            // 4) Mint 1 NFT into it
+            let cpi_ctx_mint = CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::MintTo {
+                    mint: mint.clone(),
+                    to: recipient_token_account.clone(),
+                    authority: authority.clone(),
+                },
+            );
+            token::mint_to(cpi_ctx_mint, 1)?;
+
+            // 5) Serial + event + metadata, exactly like admin_mint
+            let tier = &mut ctx.accounts.tier;
+            let serial: u16 = tier.supply_minted + 1;
+
+            let design_key = resolve_design_key(tier, serial, args.design_choice)?;
+            let data = presale_nft_data(tier, serial, args.design_choice, ctx.accounts.admin.key())?;
+            emit_cpi!(NftMinted {
+                tier_id: tier.id,
+                serial,
+                design_key,
+                mint: mint.key(),
+                recipient: recipient.key(),
+                buyer: authority.key(),
+                price_paid: 0,
+                payment_mint: NATIVE_SOL_MINT,
+                mint_path: MintPath::Airdrop,
+                uri: data.uri.clone(),
+                slot: clock.slot,
+                unix_timestamp: clock.unix_timestamp,
+            });
+
+            create_presale_nft_metadata(
+                &NftMetadataAccounts {
+                    payer: authority.clone(),
+                    mint: mint.clone(),
+                    metadata: metadata.clone(),
+                    edition: edition.clone(),
+                    global_config: global_config_info.clone(),
                     collection_mint: ctx.accounts.collection_mint.to_account_info(),
                     collection_metadata: ctx.accounts.collection_metadata.to_account_info(),
                     collection_master_edition: ctx.accounts.collection_master_edition.to_account_info(),
+                    token_metadata_program: ctx.accounts.token_metadata_program.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                    rent: ctx.accounts.rent.to_account_info(),
                 },
-                signer,
-            );
+                data,
+                ctx.bumps.global_config,
+                collection_mint_key,
+            )?;
+
+            // 6) Per-NFT record (created by hand, it is a remaining account)
+            let (record_key, record_bump) =
+                Pubkey::find_program_address(&[NFT_RECORD_SEED, mint.key.as_ref()], ctx.program_id);
+            require_keys_eq!(nft_record.key(), record_key, PresaleError::InvalidAirdropAccounts);
+            create_program_pda(
+                nft_record,
+                &authority,
+                &ctx.accounts.system_program,
+                NFT_RECORD_SPACE,
+                &[NFT_RECORD_SEED, mint.key.as_ref(), &[record_bump]],
+            )?;
+            NftRecord {
+                mint: mint.key(),
+                tier_id: tier.id,
+                serial,
+                bump: record_bump,
+                paid_lamports: 0,
+            }
+            .try_serialize(&mut &mut nft_record.try_borrow_mut_data()?[..])?;
 
-            set_and_verify_collection(cpi_ctx_collection, None)?;
+            // 7) Update counters before the next recipient's checks
+            tier.supply_minted += 1;
+            tier.admin_minted += 1;
         }
 
-        // 5) Update counters
-        tier.supply_minted += 1;
-        tier.admin_minted += 1;
-
         Ok(())
     }
-}
 
-// ---------------------------------------------
-// Tier identifiers (must match frontend JSON)
-// ---------------------------------------------
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
-pub enum TierId {
-    TreeSteel = 0,
-    Bronze = 1,
-    Silver = 2,
-    Gold = 3,
-    Platinum = 4,
-    Ws20 = 5,
-}
+    // -----------------------------------------
+    // 7) Admin: register a new tier PDA
+    // -----------------------------------------
+    pub fn create_tier(ctx: Context<CreateTier>, args: CreateTierArgs) -> Result<()> {
+        // u8::MAX is the global rate limiter scope
+        require!(
+            args.tier_id != GLOBAL_RATE_LIMIT_SCOPE,
+            PresaleError::InvalidTierId
+        );
+        require!(args.supply_total > 0, PresaleError::InvalidTierConfig);
+        require!(
+            args.supply_max >= args.supply_total,
+            PresaleError::InvalidTierConfig
+        );
+        require!(
+            args.admin_quota <= args.supply_total,
+            PresaleError::InvalidTierConfig
+        );
+        require!(
+            !args.label.is_empty() && args.label.len() <= MAX_TIER_LABEL_LEN,
+            PresaleError::InvalidTierConfig
+        );
+        require!(
+            !args.uri_base.is_empty() && args.uri_base.len() <= MAX_TIER_URI_BASE_LEN,
+            PresaleError::InvalidTierConfig
+        );
 
-impl TierId {
-    pub fn as_index(self) -> usize {
-        self as usize
+        let tier = &mut ctx.accounts.tier;
+        tier.set_inner(TierConfig::new(args, ctx.bumps.tier));
+
+        emit!(TierCreated {
+            tier_id: tier.id,
+            supply_total: tier.supply_total,
+            supply_max: tier.supply_max,
+            price_lamports: tier.price_lamports,
+            admin_quota: tier.admin_quota,
+            stake_weight: tier.stake_weight,
+            kyc_required: tier.kyc_required,
+            invite_only: tier.invite_only,
+            transferable: tier.transferable,
+        });
+
+        Ok(())
     }
-}
 
-// ---------------------------------------------
-// Tier configuration stored on-chain
-// ---------------------------------------------
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
-pub struct TierConfig {
-    pub id: u8,               // must equal TierId as integer
-    pub supply_total: u16,    // max allowed supply
-    pub supply_minted: u16,   // current mint count
-    pub admin_minted: u16,    // minted via admin_mint
-    pub price_lamports: u64,  // price in lamports
-    pub kyc_required: bool,   // true for Silver+, WS20
-    pub invite_only: bool,    // true for WS20
-    pub transferable: bool,   // false for WS20 (soulbound)
-    pub reserved: [u8; 8],    // future flags / counters (do not touch now)
-}
+    // -----------------------------------------
+    // 8) Admin: retire a tier PDA (only if nothing was minted from it)
+    // -----------------------------------------
+    pub fn close_tier(ctx: Context<CloseTier>) -> Result<()> {
+        // Serials are derived from supply_minted, so a tier that already minted
+        // must stay on-chain, otherwise re-creating it would reuse serials.
+        require!(
+            ctx.accounts.tier.supply_minted == 0,
+            PresaleError::TierHasMints
+        );
 
-impl TierConfig {
-    pub fn for_tier(tier: TierId) -> Self {
-        match tier {
-            TierId::TreeSteel => Self {
-                id: TierId::TreeSteel as u8,
-                supply_total: 2000,
-                supply_minted: 0,
-                admin_minted: 0,
-                price_lamports: 500_000_000, // 0.5 SOL
-                kyc_required: false,
-                invite_only: false,
-                transferable: true,
-                reserved: [0; 8],
-            },
-            TierId::Bronze => Self {
-                id: TierId::Bronze as u8,
-                supply_total: 1000,
-                supply_minted: 0,
-                admin_minted: 0,
-                price_lamports: 2_000_000_000, // 2 SOL
-                kyc_required: false,
-                invite_only: false,
-                transferable: true,
-                reserved: [0; 8],
-            },
-            TierId::Silver => Self {
-                id: TierId::Silver as u8,
-                supply_total: 200,
-                supply_minted: 0,
-                admin_minted: 0,
-                price_lamports: 10_000_000_000, // 10 SOL
-                kyc_required: true,
-                invite_only: false,
-                transferable: true,
-                reserved: [0; 8],
-            },
-            TierId::Gold => Self {
-                id: TierId::Gold as u8,
-                supply_total: 100,
-                supply_minted: 0,
-                admin_minted: 0,
-                price_lamports: 40_000_000_000, // 40 SOL
-                kyc_required: true,
-                invite_only: false,
-                transferable: true,
-                reserved: [0; 8],
-            },
-            TierId::Platinum => Self {
-                id: TierId::Platinum as u8,
-                supply_total: 20,
-                supply_minted: 0,
-                admin_minted: 0,
-                price_lamports: 80_000_000_000, // 80 SOL
-                kyc_required: true,
-                invite_only: false,
-                transferable: true,
-                reserved: [0; 8],
-            },
-            TierId::Ws20 => Self {
-                id: TierId::Ws20 as u8,
-                supply_total: 20,
-                supply_minted: 0,
-                admin_minted: 0,
-                price_lamports: 0, // 0 SOL
-                kyc_required: true,
-                invite_only: true,
-                transferable: false,
-                reserved: [0; 8],
-            },
-        }
+        emit!(TierClosed {
+            tier_id: ctx.accounts.tier.id,
+        });
+
+        Ok(())
     }
-}
 
-// ---------------------------------------------
-// Global configuration (PDA)
-// ---------------------------------------------
-pub const PLACEHOLDER_NAME: &str = "VIGRI Mystery NFT";
-pub const PLACEHOLDER_SYMBOL: &str = "VIGRINFT";
-pub const PLACEHOLDER_URI: &str = "https://vigri.ee/metadata/nft/vigri-mystery.json";
+    // -----------------------------------------
+    // 9) Admin: migrate GlobalConfig to the current layout version
+    // -----------------------------------------
+    pub fn migrate_config<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MigrateConfig<'info>>,
+    ) -> Result<()> {
+        let config_info = ctx.accounts.global_config.to_account_info();
+        let admin_info = ctx.accounts.admin.to_account_info();
+
+        // 1) Read admin + version from raw bytes: older layouts may not
+        //    deserialize as the current GlobalConfig
+        let from_version = {
+            let data = config_info.try_borrow_data()?;
+            require!(
+                data.len() > GLOBAL_CONFIG_VERSION_OFFSET
+                    && data.starts_with(GlobalConfig::DISCRIMINATOR),
+                PresaleError::InvalidConfigAccount
+            );
 
-// Final PDA seed for the presale global config
-pub const GLOBAL_CONFIG_SEED: &[u8] = b"vigri-presale-config";
+            let stored_admin = Pubkey::try_from(&data[8..40])
+                .map_err(|_| error!(PresaleError::InvalidConfigAccount))?;
+            require_keys_eq!(stored_admin, admin_info.key(), PresaleError::Unauthorized);
 
-// Generous space for GlobalConfig + padding + reserved
-pub const GLOBAL_CONFIG_SPACE: usize = 8 + 512;
+            data[GLOBAL_CONFIG_VERSION_OFFSET]
+        };
+        require!(
+            from_version < GLOBAL_CONFIG_VERSION,
+            PresaleError::ConfigAlreadyMigrated
+        );
 
-#[account]
-pub struct GlobalConfig {
-    pub admin: Pubkey,            // authority of the program
-    pub collection_mint: Pubkey,  // main Metaplex collection mint
-    pub payment_mint: Pubkey,     // for future SPL payments (v1 can ignore)
-    pub is_sales_paused: bool,    // global pause switch
-    pub tiers: [TierConfig; 6],   // fixed set of 6 tiers
-    pub reserved: [u8; 64],       // future use, keep zeroed
-}
+        // 2) Resize to the current layout (admin tops up or receives rent)
+        resize_program_account(
+            &config_info,
+            &admin_info,
+            &ctx.accounts.system_program,
+            GLOBAL_CONFIG_SPACE,
+        )?;
 
-impl GlobalConfig {
-    pub fn default_tiers() -> [TierConfig; 6] {
-        [
-            TierConfig::for_tier(TierId::TreeSteel),
-            TierConfig::for_tier(TierId::Bronze),
-            TierConfig::for_tier(TierId::Silver),
-            TierConfig::for_tier(TierId::Gold),
-            TierConfig::for_tier(TierId::Platinum),
-            TierConfig::for_tier(TierId::Ws20),
-        ]
-    }
-}
+        // 3) Convert layouts step by step. Raw-byte steps first: until they
+        //    ran, the data may not deserialize as the current GlobalConfig.
+        if from_version < 1 {
+            // v0 stored `[TierConfig; 6]` right after the pause flag. Tiers now
+            // live in their own PDAs, passed as remaining accounts in TierId
+            // order; the old bytes are then cleared for version + reserved.
+            let v0_tiers = read_v0_tiers(&config_info.try_borrow_data()?)?;
+            let remaining = ctx.remaining_accounts;
+            require!(
+                remaining.len() == v0_tiers.len(),
+                PresaleError::InvalidMigrationAccounts
+            );
 
-fn build_uri(tier_id: u8, serial: u16, design_choice: Option<u8>) -> Result<String> {
-    let serial6 = format!("{:06}", serial);
+            for ((tier_info, v0), tier_id) in remaining.iter().zip(&v0_tiers).zip(TierId::ALL) {
+                let (expected, bump) =
+                    Pubkey::find_program_address(&[TIER_SEED, &[tier_id as u8]], ctx.program_id);
+                require_keys_eq!(
+                    tier_info.key(),
+                    expected,
+                    PresaleError::InvalidMigrationAccounts
+                );
+
+                create_program_pda(
+                    tier_info,
+                    &admin_info,
+                    &ctx.accounts.system_program,
+                    TIER_CONFIG_SPACE,
+                    &[TIER_SEED, &[tier_id as u8], &[bump]],
+                )?;
+                TierConfig::from_v0(v0, tier_id, bump)
+                    .try_serialize(&mut &mut tier_info.try_borrow_mut_data()?[..])?;
+            }
 
-    let uri = match tier_id {
-        // 0 = Tree/Steel (choice)
-        0 => {
-            let code = match design_choice {
-                Some(1) => "TR",
-                Some(2) => "FE",
-                _ => return err!(PresaleError::InvalidDesignChoice),
-            };
-            format!(
-                "https://vigri.ee/metadata/nft/tree-steel/{}/{}.json",
-                code, serial6
-            )
+            config_info.try_borrow_mut_data()?[GLOBAL_CONFIG_VERSION_OFFSET..].fill(0);
         }
 
-        // 1 = Bronze -> CU
-        1 => format!(
-            "https://vigri.ee/metadata/nft/bronze/CU/{}.json",
-            serial6
-        ),
-
-        // 2 = Silver -> AG
-        2 => format!(
-            "https://vigri.ee/metadata/nft/silver/AG/{}.json",
-            serial6
-        ),
-
-        // 3 = Gold -> AU
-        3 => format!(
-            "https://vigri.ee/metadata/nft/gold/AU/{}.json",
-            serial6
-        ),
-
-        // 4 = Platinum -> PT
-        4 => format!(
-            "https://vigri.ee/metadata/nft/platinum/PT/{}.json",
-            serial6
-        ),
-
-        // 5 = WS20 -> WS
-        5 => format!(
-            "https://vigri.ee/metadata/nft/ws/WS/{}.json",
-            serial6
-        ),
-
-        _ => return err!(PresaleError::InvalidTierId),
-    };
+        // Later steps insert fields before `reserved`. Reserved bytes are zero
+        // and resizing zero-fills, so new fields start zeroed; only non-zero
+        // defaults need to be written here.
+        let mut global_config =
+            GlobalConfig::try_deserialize(&mut &config_info.try_borrow_data()?[..])?;
 
-    Ok(uri)
-}
+        // v1 -> v2: `pending_admin` starts as Pubkey::default() (no transfer).
+        // v2 -> v3: `roles` start unassigned (only the admin can act).
+        // v3 -> v4: `multisig` starts disabled (threshold 0).
 
-fn build_name(tier_id: u8, serial: u16, design_choice: Option<u8>) -> Result<String> {
-    // Base label by tier
-    let base_label = match tier_id {
-        0 => "Tree/Steel",
-        1 => "Bronze",
-        2 => "Silver",
-        3 => "Gold",
-        4 => "Platinum",
-        5 => "WS-20",
-        _ => return err!(PresaleError::InvalidTierId),
+        // v4 -> v5: timelock fields, nothing is pending and the delay starts
+        // at the default instead of zero.
+        if from_version < 5 {
+            global_config.config_delay_seconds = DEFAULT_CONFIG_DELAY_SECONDS;
+        }
+
+        // v5 -> v6: `rate_limited` starts false (no global throttle).
+        // v6 -> v7: `voucher_signer` starts unset (redeem_voucher disabled).
+        // v7 -> v8: no shorter config delay is pending (None).
+        // v8 -> v9: no voucher signer change is pending (None).
+
+        global_config.version = GLOBAL_CONFIG_VERSION;
+        global_config.try_serialize(&mut &mut config_info.try_borrow_mut_data()?[..])?;
+
+        emit!(ConfigMigrated {
+            from_version,
+            to_version: GLOBAL_CONFIG_VERSION,
+        });
+
+        Ok(())
+    }
+
+    // -----------------------------------------
+    // 10) Admin: propose a new admin (step 1 of 2)
+    // -----------------------------------------
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+        let global_config = &mut ctx.accounts.global_config;
+
+        require!(
+            new_admin != Pubkey::default() && new_admin != global_config.admin,
+            PresaleError::InvalidAdmin
+        );
+
+        global_config.pending_admin = new_admin;
+
+        emit!(AdminTransferProposed {
+            admin: global_config.admin,
+            proposed_admin: new_admin,
+        });
+
+        Ok(())
+    }
+
+    // -----------------------------------------
+    // 11) Proposed admin: accept the role (step 2 of 2)
+    // -----------------------------------------
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        let global_config = &mut ctx.accounts.global_config;
+
+        let previous_admin = global_config.admin;
+        global_config.admin = ctx.accounts.new_admin.key();
+        global_config.pending_admin = Pubkey::default();
+
+        emit!(AdminTransferAccepted {
+            previous_admin,
+            new_admin: global_config.admin,
+        });
+
+        Ok(())
+    }
+
+    // -----------------------------------------
+    // 12) Admin: cancel a pending admin transfer
+    // -----------------------------------------
+    pub fn cancel_admin_transfer(ctx: Context<CancelAdminTransfer>) -> Result<()> {
+        let global_config = &mut ctx.accounts.global_config;
+
+        require!(
+            global_config.pending_admin != Pubkey::default(),
+            PresaleError::NoPendingAdmin
+        );
+
+        let cancelled_admin = global_config.pending_admin;
+        global_config.pending_admin = Pubkey::default();
+
+        emit!(AdminTransferCancelled {
+            admin: global_config.admin,
+            cancelled_admin,
+        });
+
+        Ok(())
+    }
+
+    // -----------------------------------------
+    // 13) Admin: assign (or revoke with default()) a role key
+    // -----------------------------------------
+    pub fn set_role(ctx: Context<SetRole>, role: Role, key: Pubkey) -> Result<()> {
+        let global_config = &mut ctx.accounts.global_config;
+
+        let slot = global_config.roles.key_mut(role);
+        let previous = *slot;
+        *slot = key;
+
+        emit!(RoleUpdated {
+            role,
+            previous,
+            key,
+        });
+
+        Ok(())
+    }
+
+    // -----------------------------------------
+    // 14) Treasury withdrawer: move SOL out of the treasury PDA
+    // -----------------------------------------
+    pub fn withdraw_treasury(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+        let global_config = &ctx.accounts.global_config;
+
+        require!(
+            global_config.has_role(Role::TreasuryWithdrawer, ctx.accounts.authority.key()),
+            PresaleError::Unauthorized
+        );
+
+        // With a multisig configured this goes through propose/approve/execute
+        require!(!global_config.multisig.is_active(), PresaleError::MultisigRequired);
+
+        transfer_from_treasury(
+            &ctx.accounts.treasury,
+            &ctx.accounts.destination.to_account_info(),
+            &ctx.accounts.system_program,
+            ctx.bumps.treasury,
+            amount,
+        )?;
+
+        emit!(TreasuryWithdrawn {
+            authority: ctx.accounts.authority.key(),
+            destination: ctx.accounts.destination.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+
+    // -----------------------------------------
+    // 15) Admin: configure multisig signers (only while no multisig is active)
+    // -----------------------------------------
+    pub fn set_multisig(ctx: Context<SetMultisig>, signers: Vec<Pubkey>, threshold: u8) -> Result<()> {
+        let global_config = &mut ctx.accounts.global_config;
+
+        // Once active, the signer set can only change through SetMultisig proposals
+        require!(!global_config.multisig.is_active(), PresaleError::MultisigRequired);
+
+        global_config.multisig.replace_signers(&signers, threshold)?;
+
+        emit!(MultisigUpdated {
+            signers,
+            threshold,
+            signers_epoch: global_config.multisig.signers_epoch,
+        });
+
+        Ok(())
+    }
+
+    // -----------------------------------------
+    // 16) Multisig signer: propose a sensitive action (proposer approves it)
+    // -----------------------------------------
+    pub fn propose_action(ctx: Context<ProposeAction>, action: ProposedAction) -> Result<()> {
+        let global_config = &mut ctx.accounts.global_config;
+        let proposer = ctx.accounts.proposer.key();
+
+        require!(global_config.multisig.is_active(), PresaleError::MultisigNotActive);
+        let signer_index = global_config
+            .multisig
+            .signer_index(proposer)
+            .ok_or(error!(PresaleError::NotMultisigSigner))?;
+
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.id = global_config.multisig.proposal_count;
+        proposal.bump = ctx.bumps.proposal;
+        proposal.proposer = proposer;
+        proposal.action = action.clone();
+        proposal.signers_epoch = global_config.multisig.signers_epoch;
+        proposal.approvals = 1 << signer_index;
+        proposal.executed = false;
+        proposal.created_at = Clock::get()?.unix_timestamp;
+
+        global_config.multisig.proposal_count += 1;
+
+        emit!(ActionProposed {
+            proposal_id: proposal.id,
+            proposer,
+            action,
+        });
+
+        Ok(())
+    }
+
+    // -----------------------------------------
+    // 17) Multisig signer: approve a pending proposal
+    // -----------------------------------------
+    pub fn approve_action(ctx: Context<ApproveAction>) -> Result<()> {
+        let multisig = &ctx.accounts.global_config.multisig;
+        let proposal = &mut ctx.accounts.proposal;
+        let signer = ctx.accounts.signer.key();
+
+        require!(!proposal.executed, PresaleError::ProposalAlreadyExecuted);
+        require!(
+            proposal.signers_epoch == multisig.signers_epoch,
+            PresaleError::ProposalStale
+        );
+
+        let signer_index = multisig
+            .signer_index(signer)
+            .ok_or(error!(PresaleError::NotMultisigSigner))?;
+        require!(
+            proposal.approvals & (1 << signer_index) == 0,
+            PresaleError::AlreadyApproved
+        );
+
+        proposal.approvals |= 1 << signer_index;
+
+        emit!(ActionApproved {
+            proposal_id: proposal.id,
+            signer,
+            approvals: proposal.approvals.count_ones() as u8,
+        });
+
+        Ok(())
+    }
+
+    // -----------------------------------------
+    // 18) Multisig signer: execute a proposal once the threshold is met
+    // -----------------------------------------
+    pub fn execute_action(ctx: Context<ExecuteAction>) -> Result<()> {
+        let global_config = &mut ctx.accounts.global_config;
+        let proposal = &mut ctx.accounts.proposal;
+
+        require!(
+            global_config.multisig.is_signer(ctx.accounts.executor.key()),
+            PresaleError::NotMultisigSigner
+        );
+        require!(!proposal.executed, PresaleError::ProposalAlreadyExecuted);
+        require!(
+            proposal.signers_epoch == global_config.multisig.signers_epoch,
+            PresaleError::ProposalStale
+        );
+        require!(
+            proposal.approvals.count_ones() >= global_config.multisig.threshold as u32,
+            PresaleError::ThresholdNotMet
+        );
+
+        match proposal.action.clone() {
+            ProposedAction::SetTierPrice { tier_id, price_lamports } => {
+                let tier = ctx
+                    .accounts
+                    .tier
+                    .as_mut()
+                    .ok_or(error!(PresaleError::MissingActionAccount))?;
+                require!(tier.id == tier_id, PresaleError::InvalidTierId);
+
+                // Approved price changes are still subject to the timelock;
+                // other queued fields of the tier are kept
+                tier.queue_change(TierChange {
+                    price_lamports: Some(price_lamports),
+                    effective_at: global_config.resolve_effective_at(None)?,
+                    ..Default::default()
+                });
+            }
+            ProposedAction::SetCollectionMint { collection_mint } => {
+                let new_collection_mint = ctx
+                    .accounts
+                    .new_collection_mint
+                    .as_ref()
+                    .ok_or(error!(PresaleError::MissingActionAccount))?;
+                require_keys_eq!(
+                    new_collection_mint.key(),
+                    collection_mint,
+                    PresaleError::MissingActionAccount
+                );
+
+                global_config.queue_collection_mint(collection_mint, None)?;
+            }
+            ProposedAction::WithdrawTreasury { destination, amount } => {
+                let (Some(treasury), Some(destination_account)) =
+                    (ctx.accounts.treasury.as_ref(), ctx.accounts.destination.as_ref())
+                else {
+                    return err!(PresaleError::MissingActionAccount);
+                };
+                require_keys_eq!(
+                    destination_account.key(),
+                    destination,
+                    PresaleError::MissingActionAccount
+                );
+
+                let treasury_bump = ctx
+                    .bumps
+                    .treasury
+                    .ok_or(error!(PresaleError::MissingActionAccount))?;
+
+                transfer_from_treasury(
+                    treasury,
+                    &destination_account.to_account_info(),
+                    &ctx.accounts.system_program,
+                    treasury_bump,
+                    amount,
+                )?;
+            }
+            ProposedAction::SetMultisig { signers, signer_count, threshold } => {
+                require!(
+                    signer_count as usize <= MAX_MULTISIG_SIGNERS,
+                    PresaleError::InvalidMultisig
+                );
+                // No signers and threshold 0 switch the multisig off again
+                if signer_count == 0 && threshold == 0 {
+                    global_config.multisig.disable();
+                } else {
+                    let signers = &signers[..signer_count as usize];
+                    global_config.multisig.replace_signers(signers, threshold)?;
+                }
+
+                emit!(MultisigUpdated {
+                    signers: global_config.multisig.signers[..signer_count as usize].to_vec(),
+                    threshold,
+                    signers_epoch: global_config.multisig.signers_epoch,
+                });
+            }
+            ProposedAction::SetBuybackBudget { budget_lamports } => {
+                let buyback = ctx
+                    .accounts
+                    .buyback_config
+                    .as_mut()
+                    .ok_or(error!(PresaleError::MissingActionAccount))?;
+                buyback.budget_lamports = budget_lamports;
+
+                emit!(BuybackConfigUpdated {
+                    is_paused: buyback.is_paused,
+                    budget_lamports: buyback.budget_lamports,
+                    spent_lamports: buyback.spent_lamports,
+                    burn_returned: buyback.burn_returned,
+                });
+            }
+            ProposedAction::ReleaseBuybackNft { nft_mint, destination } => {
+                let (
+                    Some(treasury),
+                    Some(buyback_vault),
+                    Some(vault_token_account),
+                    Some(destination_token_account),
+                    Some(token_program),
+                ) = (
+                    ctx.accounts.treasury.as_ref(),
+                    ctx.accounts.buyback_vault.as_ref(),
+                    ctx.accounts.vault_token_account.as_ref(),
+                    ctx.accounts.destination_token_account.as_ref(),
+                    ctx.accounts.token_program.as_ref(),
+                )
+                else {
+                    return err!(PresaleError::MissingActionAccount);
+                };
+                require!(
+                    vault_token_account.mint == nft_mint
+                        && destination_token_account.owner == destination,
+                    PresaleError::MissingActionAccount
+                );
+
+                let vault_bump = ctx
+                    .bumps
+                    .buyback_vault
+                    .ok_or(error!(PresaleError::MissingActionAccount))?;
+
+                release_from_buyback_vault(
+                    vault_token_account,
+                    destination_token_account,
+                    &buyback_vault.to_account_info(),
+                    &treasury.to_account_info(),
+                    &token_program.to_account_info(),
+                    vault_bump,
+                )?;
+
+                emit!(BuybackNftReleased {
+                    nft_mint,
+                    destination,
+                });
+            }
+            ProposedAction::SetVoucherSigner { voucher_signer } => {
+                // Approved signers are still subject to the timelock
+                global_config.queue_voucher_signer(voucher_signer)?;
+            }
+        }
+
+        proposal.executed = true;
+
+        emit!(ActionExecuted {
+            proposal_id: proposal.id,
+            executor: ctx.accounts.executor.key(),
+        });
+
+        Ok(())
+    }
+
+    // -----------------------------------------
+    // 19) Anyone: apply timelocked changes whose effective time has passed
+    // -----------------------------------------
+    pub fn apply_pending_config(ctx: Context<ApplyPendingConfig>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let global_config = &mut ctx.accounts.global_config;
+
+        let mut applied = false;
+
+        // 1) Collection mint change
+        if global_config.pending_collection_mint != Pubkey::default()
+            && global_config.pending_collection_effective_at <= now
+        {
+            emit!(CollectionMintChanged {
+                old_collection_mint: global_config.collection_mint,
+                new_collection_mint: global_config.pending_collection_mint,
+            });
+
+            global_config.collection_mint = global_config.pending_collection_mint;
+            global_config.pending_collection_mint = Pubkey::default();
+            global_config.pending_collection_effective_at = 0;
+            applied = true;
+        }
+
+        // 2) Tier change (optional tier account)
+        if let Some(tier) = ctx.accounts.tier.as_mut() {
+            if let Some(change) = tier.apply_pending_if_due(now) {
+                emit!(TierChangeApplied {
+                    tier_id: tier.id,
+                    change,
+                });
+                applied = true;
+            }
+        }
+
+        // 3) Shorter config delay
+        if let Some(delay_seconds) = global_config.pending_config_delay {
+            if global_config.pending_config_delay_effective_at <= now {
+                global_config.config_delay_seconds = delay_seconds;
+                global_config.pending_config_delay = None;
+                global_config.pending_config_delay_effective_at = 0;
+
+                emit!(ConfigDelayUpdated { delay_seconds });
+                applied = true;
+            }
+        }
+
+        // 4) Voucher signer
+        if let Some(voucher_signer) = global_config.pending_voucher_signer {
+            if global_config.pending_voucher_signer_effective_at <= now {
+                emit!(VoucherSignerUpdated {
+                    old_signer: global_config.voucher_signer,
+                    new_signer: voucher_signer,
+                });
+
+                global_config.voucher_signer = voucher_signer;
+                global_config.pending_voucher_signer = None;
+                global_config.pending_voucher_signer_effective_at = 0;
+                applied = true;
+            }
+        }
+
+        require!(applied, PresaleError::NoPendingChange);
+
+        Ok(())
+    }
+
+    // -----------------------------------------
+    // 20) Admin: set the minimum delay for timelocked changes (decreases are timelocked)
+    // -----------------------------------------
+    pub fn set_config_delay(ctx: Context<SetConfigDelay>, delay_seconds: i64) -> Result<()> {
+        require!(
+            (0..=MAX_CONFIG_DELAY_SECONDS).contains(&delay_seconds),
+            PresaleError::InvalidConfigDelay
+        );
+
+        let global_config = &mut ctx.accounts.global_config;
+
+        if delay_seconds >= global_config.config_delay_seconds {
+            // A longer delay only adds protection: apply it now and drop any
+            // shorter delay still waiting
+            global_config.config_delay_seconds = delay_seconds;
+            global_config.pending_config_delay = None;
+            global_config.pending_config_delay_effective_at = 0;
+
+            emit!(ConfigDelayUpdated { delay_seconds });
+        } else {
+            // A shorter delay has to wait out the current one
+            let effective_at = global_config.resolve_effective_at(None)?;
+            global_config.pending_config_delay = Some(delay_seconds);
+            global_config.pending_config_delay_effective_at = effective_at;
+
+            emit!(ConfigDelayChangeQueued {
+                delay_seconds,
+                effective_at,
+            });
+        }
+
+        Ok(())
+    }
+
+    // -----------------------------------------
+    // 21) Admin: configure a mint throttle (max_mints per window_slots)
+    // -----------------------------------------
+    // scope = tier id, or GLOBAL_RATE_LIMIT_SCOPE for all tiers together.
+    // max_mints = 0 switches the throttle off (the PDA stays for reuse).
+    pub fn set_rate_limit(
+        ctx: Context<SetRateLimit>,
+        scope: u8,
+        max_mints: u16,
+        window_slots: u64,
+    ) -> Result<()> {
+        require!(
+            max_mints as usize <= MAX_RATE_LIMIT_MINTS && (max_mints == 0 || window_slots > 0),
+            PresaleError::InvalidRateLimit
+        );
+
+        let enabled = max_mints > 0;
+        if scope == GLOBAL_RATE_LIMIT_SCOPE {
+            ctx.accounts.global_config.rate_limited = enabled;
+        } else {
+            let tier = ctx
+                .accounts
+                .tier
+                .as_mut()
+                .ok_or(error!(PresaleError::InvalidTierId))?;
+            require!(tier.id == scope, PresaleError::InvalidTierId);
+            tier.rate_limited = enabled;
+        }
+
+        let rate_limiter = &mut ctx.accounts.rate_limiter;
+        rate_limiter.scope = scope;
+        rate_limiter.bump = ctx.bumps.rate_limiter;
+        rate_limiter.configure(max_mints, window_slots);
+
+        emit!(RateLimitUpdated {
+            scope,
+            max_mints,
+            window_slots,
+        });
+
+        Ok(())
+    }
+
+    // -----------------------------------------
+    // 22) Holder: burn an NFT and mint one of a higher tier for the price delta
+    // -----------------------------------------
+    pub fn upgrade_nft(ctx: Context<UpgradeNft>, args: UpgradeNftArgs) -> Result<()> {
+        let global_config_info = ctx.accounts.global_config.to_account_info();
+        let global_config = &ctx.accounts.global_config;
+        require!(!global_config.is_sales_paused, PresaleError::SalesPaused);
+
+        // Vesting follows the mint (ClaimRecord), so the new NFT would start
+        // its allocation from zero: NFTs with claims cannot be upgraded
+        require!(
+            ctx.accounts.old_claim_record.data_is_empty(),
+            PresaleError::NftHasClaims
+        );
+
+        // 1) Both tiers are priced with any due timelocked change applied
+        let now = Clock::get()?.unix_timestamp;
+        for tier in [&mut ctx.accounts.from_tier, &mut ctx.accounts.to_tier] {
+            if let Some(change) = tier.apply_pending_if_due(now) {
+                emit!(TierChangeApplied {
+                    tier_id: tier.id,
+                    change,
+                });
+            }
+        }
+
+        let from_tier = &ctx.accounts.from_tier;
+        let to_tier = &ctx.accounts.to_tier;
+
+        // 2) Target tier must be open and strictly more expensive
+        require!(!to_tier.is_paused, PresaleError::TierPaused);
+        require!(to_tier.supply_minted < to_tier.supply_total, PresaleError::TierSoldOut);
+        require!(to_tier.price_lamports > 0, PresaleError::TierPriceNotSet);
+        require!(
+            to_tier.id != from_tier.id && to_tier.price_lamports > from_tier.price_lamports,
+            PresaleError::InvalidUpgrade
+        );
+
+        if to_tier.kyc_required {
+            check_kyc(ctx.accounts.kyc_record.as_deref(), now)?;
+        }
+        if to_tier.invite_only {
+            require!(args.invite_proof.is_some(), PresaleError::InviteRequired);
+        }
+
+        // 2a) An upgrade mints from to_tier, so the same sale protections apply
+        if to_tier.anti_bot_max_mints > 0 {
+            let instructions = ctx
+                .accounts
+                .instructions
+                .as_ref()
+                .ok_or(error!(PresaleError::AntiBotRejected))?;
+            check_anti_bot(instructions, to_tier.anti_bot_max_mints)?;
+        }
+
+        let slot = Clock::get()?.slot;
+        if global_config.rate_limited {
+            ctx.accounts
+                .global_rate_limiter
+                .as_mut()
+                .ok_or(error!(PresaleError::MissingRateLimiter))?
+                .record_mint(slot)?;
+        }
+        if to_tier.rate_limited {
+            ctx.accounts
+                .tier_rate_limiter
+                .as_mut()
+                .ok_or(error!(PresaleError::MissingRateLimiter))?
+                .record_mint(slot)?;
+        }
+
+        // 3) The burned NFT must be ours and belong to from_tier
+        verify_presale_nft(
+            &ctx.accounts.old_metadata,
+            ctx.accounts.old_mint.key(),
+            global_config,
+            global_config_info.key(),
+            &ctx.accounts.old_nft_record,
+            from_tier,
+        )?;
+
+        // 4) Burn it (token, mint supply, metadata and master edition)
+        let cpi_ctx_burn = CpiContext::new(
+            ctx.accounts.token_metadata_program.to_account_info(),
+            BurnNft {
+                metadata: ctx.accounts.old_metadata.to_account_info(),
+                owner: ctx.accounts.owner.to_account_info(),
+                mint: ctx.accounts.old_mint.to_account_info(),
+                token: ctx.accounts.old_token_account.to_account_info(),
+                edition: ctx.accounts.old_edition.to_account_info(),
+                spl_token: ctx.accounts.token_program.to_account_info(),
+            },
+        )
+        // Verified collection: Metaplex decrements the collection size too
+        .with_remaining_accounts(vec![ctx.accounts.collection_metadata.to_account_info()]);
+        burn_nft(cpi_ctx_burn, Some(ctx.accounts.collection_metadata.key()))?;
+
+        // 5) Price delta: owner -> treasury PDA
+        let price_delta = to_tier.price_lamports - from_tier.price_lamports;
+        let cpi_ctx_transfer = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.owner.to_account_info(),
+                to: ctx.accounts.treasury.to_account_info(),
+            },
+        );
+        system_program::transfer(cpi_ctx_transfer, price_delta)?;
+
+        // 6) Mint the new NFT with a fresh serial of to_tier
+        let cpi_ctx_mint = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token::MintTo {
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.owner_token_account.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            },
+        );
+        token::mint_to(cpi_ctx_mint, 1)?;
+
+        let collection_mint_key = global_config.collection_mint;
+        let to_tier = &ctx.accounts.to_tier;
+        let serial: u16 = to_tier.supply_minted + 1;
+
+        let design_key = resolve_design_key(to_tier, serial, args.design_choice)?;
+        let data = presale_nft_data(to_tier, serial, args.design_choice, ctx.accounts.admin.key())?;
+        let clock = Clock::get()?;
+        emit_cpi!(NftMinted {
+            tier_id: to_tier.id,
+            serial,
+            design_key,
+            mint: ctx.accounts.mint.key(),
+            recipient: ctx.accounts.owner.key(),
+            buyer: ctx.accounts.owner.key(),
+            price_paid: price_delta,
+            payment_mint: NATIVE_SOL_MINT,
+            mint_path: MintPath::Upgrade,
+            uri: data.uri.clone(),
+            slot: clock.slot,
+            unix_timestamp: clock.unix_timestamp,
+        });
+        emit_cpi!(NftUpgraded {
+            owner: ctx.accounts.owner.key(),
+            old_mint: ctx.accounts.old_mint.key(),
+            from_tier_id: ctx.accounts.from_tier.id,
+            new_mint: ctx.accounts.mint.key(),
+            to_tier_id: to_tier.id,
+            serial,
+            price_paid: price_delta,
+        });
+
+        create_presale_nft_metadata(
+            &NftMetadataAccounts {
+                payer: ctx.accounts.owner.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                metadata: ctx.accounts.metadata.to_account_info(),
+                edition: ctx.accounts.edition.to_account_info(),
+                global_config: global_config_info,
+                collection_mint: ctx.accounts.collection_mint.to_account_info(),
+                collection_metadata: ctx.accounts.collection_metadata.to_account_info(),
+                collection_master_edition: ctx.accounts.collection_master_edition.to_account_info(),
+                token_metadata_program: ctx.accounts.token_metadata_program.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                rent: ctx.accounts.rent.to_account_info(),
+            },
+            data,
+            ctx.bumps.global_config,
+            collection_mint_key,
+        )?;
+
+        // 7) Per-NFT record + counters: serials of from_tier are never reused,
+        //    so only count the burn
+        ctx.accounts.nft_record.set_inner(NftRecord {
+            mint: ctx.accounts.mint.key(),
+            tier_id: ctx.accounts.to_tier.id,
+            serial,
+            bump: ctx.bumps.nft_record,
+            paid_lamports: ctx
+                .accounts
+                .old_nft_record
+                .paid_lamports
+                .saturating_add(price_delta),
+        });
+        ctx.accounts.from_tier.supply_burned += 1;
+        ctx.accounts.to_tier.supply_minted += 1;
+
+        Ok(())
+    }
+
+    // -----------------------------------------
+    // 23) Admin: set the token allocation and vesting schedule of a tier
+    // -----------------------------------------
+    // The vault (one per token mint) is funded with plain SPL transfers.
+    pub fn set_tier_allocation(
+        ctx: Context<SetTierAllocation>,
+        args: SetTierAllocationArgs,
+    ) -> Result<()> {
+        require!(
+            args.cliff_seconds >= 0 && args.vesting_seconds >= 0,
+            PresaleError::InvalidVestingSchedule
+        );
+
+        let allocation = &mut ctx.accounts.allocation;
+
+        // Once holders have claimed, the token is fixed and the schedule may
+        // only move in their favour
+        if allocation.total_claimed > 0 {
+            require!(
+                allocation.token_mint == ctx.accounts.token_mint.key(),
+                PresaleError::AllocationLocked
+            );
+            require!(
+                allocation.is_unlocked_no_later_by(&args),
+                PresaleError::AllocationScheduleLocked
+            );
+        }
+
+        allocation.tier_id = ctx.accounts.tier.id;
+        allocation.bump = ctx.bumps.allocation;
+        allocation.token_mint = ctx.accounts.token_mint.key();
+        allocation.amount_per_nft = args.amount_per_nft;
+        allocation.vesting_start_ts = args.vesting_start_ts;
+        allocation.cliff_seconds = args.cliff_seconds;
+        allocation.vesting_seconds = args.vesting_seconds;
+
+        emit!(TierAllocationUpdated {
+            tier_id: allocation.tier_id,
+            token_mint: allocation.token_mint,
+            amount_per_nft: args.amount_per_nft,
+            vesting_start_ts: args.vesting_start_ts,
+            cliff_seconds: args.cliff_seconds,
+            vesting_seconds: args.vesting_seconds,
+        });
+
+        Ok(())
+    }
+
+    // -----------------------------------------
+    // 24) Holder: claim the unlocked part of one NFT's token allocation
+    // -----------------------------------------
+    // The ClaimRecord PDA is keyed by the NFT mint, so vesting follows the
+    // NFT: a buyer on secondary claims whatever the seller left unclaimed.
+    pub fn claim_allocation(ctx: Context<ClaimAllocation>) -> Result<()> {
+        let allocation = &ctx.accounts.allocation;
+        require!(allocation.amount_per_nft > 0, PresaleError::AllocationNotSet);
+
+        let now = Clock::get()?.unix_timestamp;
+        let amount = allocation
+            .unlocked_amount(now)
+            .saturating_sub(ctx.accounts.claim_record.claimed_amount);
+        require!(amount > 0, PresaleError::NothingToClaim);
+
+        // 1) NFT must be ours and belong to the allocation's tier
+        verify_presale_nft(
+            &ctx.accounts.nft_metadata,
+            ctx.accounts.nft_mint.key(),
+            &ctx.accounts.global_config,
+            ctx.accounts.global_config.key(),
+            &ctx.accounts.nft_record,
+            &ctx.accounts.tier,
+        )?;
+
+        // 2) Vault -> holder, signed by the vault PDA
+        let token_mint_key = ctx.accounts.token_mint.key();
+        let signer_seeds: &[&[u8]] = &[
+            ALLOCATION_VAULT_SEED,
+            token_mint_key.as_ref(),
+            &[ctx.bumps.vault],
+        ];
+        let signer: &[&[&[u8]]] = &[signer_seeds];
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            token::TransferChecked {
+                from: ctx.accounts.vault.to_account_info(),
+                mint: ctx.accounts.token_mint.to_account_info(),
+                to: ctx.accounts.holder_token_account.to_account_info(),
+                authority: ctx.accounts.vault.to_account_info(),
+            },
+            signer,
+        );
+        token::transfer_checked(cpi_ctx, amount, ctx.accounts.token_mint.decimals)?;
+
+        // 3) Record the claim (first claim creates the record)
+        let claim_record = &mut ctx.accounts.claim_record;
+        claim_record.nft_mint = ctx.accounts.nft_mint.key();
+        claim_record.tier_id = ctx.accounts.tier.id;
+        claim_record.bump = ctx.bumps.claim_record;
+        claim_record.claimant = ctx.accounts.holder.key();
+        claim_record.claimed_amount += amount;
+        claim_record.claimed_at = now;
+
+        let allocation = &mut ctx.accounts.allocation;
+        allocation.total_claimed = allocation.total_claimed.saturating_add(amount);
+
+        emit!(AllocationClaimed {
+            tier_id: claim_record.tier_id,
+            nft_mint: claim_record.nft_mint,
+            claimant: claim_record.claimant,
+            amount,
+            claimed_total: claim_record.claimed_amount,
+        });
+
+        Ok(())
+    }
+
+    // -----------------------------------------
+    // 25) Holder: stake an NFT (stays in the wallet, frozen via a PDA delegate)
+    // -----------------------------------------
+    pub fn stake_nft(ctx: Context<StakeNft>) -> Result<()> {
+        // 1) NFT must be ours and belong to the passed tier
+        verify_presale_nft(
+            &ctx.accounts.nft_metadata,
+            ctx.accounts.nft_mint.key(),
+            &ctx.accounts.global_config,
+            ctx.accounts.global_config.key(),
+            &ctx.accounts.nft_record,
+            &ctx.accounts.tier,
+        )?;
+
+        // 2) Delegate the token to the stake authority PDA ...
+        let cpi_ctx_approve = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token::Approve {
+                to: ctx.accounts.nft_token_account.to_account_info(),
+                delegate: ctx.accounts.stake_authority.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            },
+        );
+        token::approve(cpi_ctx_approve, 1)?;
+
+        // 3) ... which freezes it through Metaplex (the edition is the freeze authority)
+        let signer_seeds: &[&[u8]] = &[STAKE_AUTHORITY_SEED, &[ctx.bumps.stake_authority]];
+        let signer: &[&[&[u8]]] = &[signer_seeds];
+
+        let cpi_ctx_freeze = CpiContext::new_with_signer(
+            ctx.accounts.token_metadata_program.to_account_info(),
+            FreezeDelegatedAccount {
+                metadata: ctx.accounts.nft_metadata.to_account_info(),
+                delegate: ctx.accounts.stake_authority.to_account_info(),
+                token_account: ctx.accounts.nft_token_account.to_account_info(),
+                edition: ctx.accounts.nft_edition.to_account_info(),
+                mint: ctx.accounts.nft_mint.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
+            signer,
+        );
+        freeze_delegated_account(cpi_ctx_freeze)?;
+
+        // 4) Record the stake
+        let now = Clock::get()?.unix_timestamp;
+        let stake_record = &mut ctx.accounts.stake_record;
+        stake_record.nft_mint = ctx.accounts.nft_mint.key();
+        stake_record.owner = ctx.accounts.owner.key();
+        stake_record.tier_id = ctx.accounts.tier.id;
+        stake_record.bump = ctx.bumps.stake_record;
+        stake_record.staked_at = now;
+        stake_record.stake_weight = ctx.accounts.tier.stake_weight;
+
+        let points = &mut ctx.accounts.staker_points;
+        points.owner = ctx.accounts.owner.key();
+        points.bump = ctx.bumps.staker_points;
+        points.staked_count += 1;
+
+        emit!(NftStaked {
+            owner: stake_record.owner,
+            nft_mint: stake_record.nft_mint,
+            tier_id: stake_record.tier_id,
+            staked_at: now,
+            stake_weight: stake_record.stake_weight,
+        });
+
+        Ok(())
+    }
+
+    // -----------------------------------------
+    // 26) Holder: unstake at any time and book the accrued points
+    // -----------------------------------------
+    pub fn unstake_nft(ctx: Context<UnstakeNft>) -> Result<()> {
+        // 1) Thaw (stake authority PDA signs as delegate), then drop the delegate
+        let signer_seeds: &[&[u8]] = &[STAKE_AUTHORITY_SEED, &[ctx.bumps.stake_authority]];
+        let signer: &[&[&[u8]]] = &[signer_seeds];
+
+        let cpi_ctx_thaw = CpiContext::new_with_signer(
+            ctx.accounts.token_metadata_program.to_account_info(),
+            ThawDelegatedAccount {
+                metadata: ctx.accounts.nft_metadata.to_account_info(),
+                delegate: ctx.accounts.stake_authority.to_account_info(),
+                token_account: ctx.accounts.nft_token_account.to_account_info(),
+                edition: ctx.accounts.nft_edition.to_account_info(),
+                mint: ctx.accounts.nft_mint.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
+            signer,
+        );
+        thaw_delegated_account(cpi_ctx_thaw)?;
+
+        let cpi_ctx_revoke = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token::Revoke {
+                source: ctx.accounts.nft_token_account.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            },
+        );
+        token::revoke(cpi_ctx_revoke)?;
+
+        // 2) Points = weight x seconds staked, with the tier weight fixed at
+        // stake time so later stake_weight changes don't rewrite past stakes
+        let now = Clock::get()?.unix_timestamp;
+        let stake_record = &ctx.accounts.stake_record;
+        let seconds = now.saturating_sub(stake_record.staked_at).max(0) as u64;
+        let earned = seconds.saturating_mul(stake_record.stake_weight as u64);
+
+        let points = &mut ctx.accounts.staker_points;
+        points.points = points.points.saturating_add(earned);
+        points.staked_count = points.staked_count.saturating_sub(1);
+
+        emit!(NftUnstaked {
+            owner: ctx.accounts.owner.key(),
+            nft_mint: ctx.accounts.nft_mint.key(),
+            tier_id: ctx.accounts.tier.id,
+            staked_seconds: seconds,
+            points_earned: earned,
+            points_total: points.points,
+        });
+
+        // StakeRecord is closed to the owner by the accounts constraint
+        Ok(())
+    }
+
+    // -----------------------------------------
+    // 27) Holder: request the physical item for an NFT (locks the NFT)
+    // -----------------------------------------
+    // Flow: Requested -> Approved -> Shipped (admin) -> Completed (holder, burns;
+    // or the admin once REDEMPTION_CONFIRM_TIMEOUT_SECONDS have passed).
+    // The admin may move Requested / Approved to Rejected; the holder can then
+    // (or while still Requested) cancel to unlock the NFT.
+    pub fn request_redemption(
+        ctx: Context<RequestRedemption>,
+        shipping_info_hash: [u8; 32],
+    ) -> Result<()> {
+        // 1) NFT must be ours and belong to the passed tier
+        verify_presale_nft(
+            &ctx.accounts.nft_metadata,
+            ctx.accounts.nft_mint.key(),
+            &ctx.accounts.global_config,
+            ctx.accounts.global_config.key(),
+            &ctx.accounts.nft_record,
+            &ctx.accounts.tier,
+        )?;
+
+        // 2) Lock: delegate to the redemption authority PDA and freeze
+        let cpi_ctx_approve = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token::Approve {
+                to: ctx.accounts.nft_token_account.to_account_info(),
+                delegate: ctx.accounts.redemption_authority.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            },
+        );
+        token::approve(cpi_ctx_approve, 1)?;
+
+        let signer_seeds: &[&[u8]] = &[
+            REDEMPTION_AUTHORITY_SEED,
+            &[ctx.bumps.redemption_authority],
+        ];
+        let signer: &[&[&[u8]]] = &[signer_seeds];
+
+        let cpi_ctx_freeze = CpiContext::new_with_signer(
+            ctx.accounts.token_metadata_program.to_account_info(),
+            FreezeDelegatedAccount {
+                metadata: ctx.accounts.nft_metadata.to_account_info(),
+                delegate: ctx.accounts.redemption_authority.to_account_info(),
+                token_account: ctx.accounts.nft_token_account.to_account_info(),
+                edition: ctx.accounts.nft_edition.to_account_info(),
+                mint: ctx.accounts.nft_mint.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
+            signer,
+        );
+        freeze_delegated_account(cpi_ctx_freeze)?;
+
+        // 3) Open the request
+        let now = Clock::get()?.unix_timestamp;
+        let redemption = &mut ctx.accounts.redemption;
+        redemption.nft_mint = ctx.accounts.nft_mint.key();
+        redemption.owner = ctx.accounts.owner.key();
+        redemption.tier_id = ctx.accounts.tier.id;
+        redemption.bump = ctx.bumps.redemption;
+        redemption.status = RedemptionStatus::Requested;
+        redemption.shipping_info_hash = shipping_info_hash;
+        redemption.requested_at = now;
+        redemption.updated_at = now;
+
+        emit!(RedemptionRequested {
+            nft_mint: redemption.nft_mint,
+            owner: redemption.owner,
+            tier_id: redemption.tier_id,
+            shipping_info_hash,
+        });
+
+        Ok(())
+    }
+
+    // -----------------------------------------
+    // 28) Admin: move a redemption to Approved / Shipped / Rejected
+    // -----------------------------------------
+    pub fn set_redemption_status(
+        ctx: Context<SetRedemptionStatus>,
+        status: RedemptionStatus,
+    ) -> Result<()> {
+        let redemption = &mut ctx.accounts.redemption;
+        let old_status = redemption.status;
+
+        let allowed = matches!(
+            (old_status, status),
+            (RedemptionStatus::Requested, RedemptionStatus::Approved)
+                | (RedemptionStatus::Approved, RedemptionStatus::Shipped)
+                | (RedemptionStatus::Requested, RedemptionStatus::Rejected)
+                | (RedemptionStatus::Approved, RedemptionStatus::Rejected)
+        );
+        require!(allowed, PresaleError::InvalidRedemptionStatus);
+
+        redemption.status = status;
+        redemption.updated_at = Clock::get()?.unix_timestamp;
+
+        emit!(RedemptionStatusChanged {
+            nft_mint: redemption.nft_mint,
+            old_status,
+            new_status: status,
+        });
+
+        Ok(())
+    }
+
+    // -----------------------------------------
+    // 29) Holder: cancel a Requested or Rejected redemption (unlocks the NFT)
+    // -----------------------------------------
+    pub fn cancel_redemption(ctx: Context<CancelRedemption>) -> Result<()> {
+        let status = ctx.accounts.redemption.status;
+        require!(
+            matches!(status, RedemptionStatus::Requested | RedemptionStatus::Rejected),
+            PresaleError::InvalidRedemptionStatus
+        );
+
+        unlock_redeemed_nft(
+            &RedemptionLockAccounts {
+                owner: ctx.accounts.owner.to_account_info(),
+                redemption_authority: ctx.accounts.redemption_authority.to_account_info(),
+                nft_mint: ctx.accounts.nft_mint.to_account_info(),
+                nft_token_account: ctx.accounts.nft_token_account.to_account_info(),
+                nft_metadata: ctx.accounts.nft_metadata.to_account_info(),
+                nft_edition: ctx.accounts.nft_edition.to_account_info(),
+                token_metadata_program: ctx.accounts.token_metadata_program.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
+            ctx.bumps.redemption_authority,
+        )?;
+
+        emit!(RedemptionCancelled {
+            nft_mint: ctx.accounts.nft_mint.key(),
+            owner: ctx.accounts.owner.key(),
+        });
+
+        // Redemption record is closed to the owner by the accounts constraint
+        Ok(())
+    }
+
+    // -----------------------------------------
+    // 30) Holder: confirm receipt of a Shipped item, burning the NFT
+    // -----------------------------------------
+    pub fn complete_redemption(ctx: Context<CompleteRedemption>) -> Result<()> {
+        require!(
+            ctx.accounts.redemption.status == RedemptionStatus::Shipped,
+            PresaleError::InvalidRedemptionStatus
+        );
+
+        // 1) Unlock so the owner can burn
+        unlock_redeemed_nft(
+            &RedemptionLockAccounts {
+                owner: ctx.accounts.owner.to_account_info(),
+                redemption_authority: ctx.accounts.redemption_authority.to_account_info(),
+                nft_mint: ctx.accounts.nft_mint.to_account_info(),
+                nft_token_account: ctx.accounts.nft_token_account.to_account_info(),
+                nft_metadata: ctx.accounts.nft_metadata.to_account_info(),
+                nft_edition: ctx.accounts.nft_edition.to_account_info(),
+                token_metadata_program: ctx.accounts.token_metadata_program.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
+            ctx.bumps.redemption_authority,
+        )?;
+
+        // 2) Burn (token, mint supply, metadata and master edition)
+        let cpi_ctx_burn = CpiContext::new(
+            ctx.accounts.token_metadata_program.to_account_info(),
+            BurnNft {
+                metadata: ctx.accounts.nft_metadata.to_account_info(),
+                owner: ctx.accounts.owner.to_account_info(),
+                mint: ctx.accounts.nft_mint.to_account_info(),
+                token: ctx.accounts.nft_token_account.to_account_info(),
+                edition: ctx.accounts.nft_edition.to_account_info(),
+                spl_token: ctx.accounts.token_program.to_account_info(),
+            },
+        )
+        .with_remaining_accounts(vec![ctx.accounts.collection_metadata.to_account_info()]);
+        burn_nft(cpi_ctx_burn, Some(ctx.accounts.collection_metadata.key()))?;
+
+        // 3) Keep the record as proof of redemption
+        let redemption = &mut ctx.accounts.redemption;
+        redemption.status = RedemptionStatus::Completed;
+        redemption.updated_at = Clock::get()?.unix_timestamp;
+        ctx.accounts.tier.supply_redeemed += 1;
+
+        emit!(RedemptionCompleted {
+            nft_mint: redemption.nft_mint,
+            owner: redemption.owner,
+            tier_id: redemption.tier_id,
+        });
+
+        Ok(())
+    }
+
+    // -----------------------------------------
+    // 31) Admin: record the certificate of authenticity for (tier, serial)
+    // -----------------------------------------
+    // Re-running it replaces the hash (e.g. after a new assay); the mint
+    // binding is fixed by the first write.
+    pub fn set_certificate(
+        ctx: Context<SetCertificate>,
+        serial: u16,
+        certificate_hash: [u8; 32],
+    ) -> Result<()> {
+        let tier = &ctx.accounts.tier;
+        require!(
+            serial >= 1 && serial <= tier.supply_minted,
+            PresaleError::InvalidCertificate
+        );
+
+        // The mint must be our NFT of this tier carrying this serial
+        verify_presale_nft(
+            &ctx.accounts.nft_metadata,
+            ctx.accounts.nft_mint.key(),
+            &ctx.accounts.global_config,
+            ctx.accounts.global_config.key(),
+            &ctx.accounts.nft_record,
+            tier,
+        )?;
+        require!(
+            ctx.accounts.nft_record.serial == serial,
+            PresaleError::InvalidCertificate
+        );
+
+        let certificate = &mut ctx.accounts.certificate;
+        let now = Clock::get()?.unix_timestamp;
+        if certificate.mint == Pubkey::default() {
+            certificate.tier_id = tier.id;
+            certificate.serial = serial;
+            certificate.bump = ctx.bumps.certificate;
+            certificate.mint = ctx.accounts.nft_mint.key();
+            certificate.issued_at = now;
+        }
+        require_keys_eq!(
+            certificate.mint,
+            ctx.accounts.nft_mint.key(),
+            PresaleError::InvalidCertificate
+        );
+
+        certificate.certificate_hash = certificate_hash;
+        certificate.updated_at = now;
+
+        emit!(CertificateRecorded {
+            tier_id: certificate.tier_id,
+            serial,
+            mint: certificate.mint,
+            certificate_hash,
+        });
+
+        Ok(())
+    }
+
+    // -----------------------------------------
+    // 32) Admin / pauser: configure the buyback (budget, pause, burn or hold)
+    // -----------------------------------------
+    pub fn set_buyback_config(
+        ctx: Context<SetBuybackConfig>,
+        args: SetBuybackConfigArgs,
+    ) -> Result<()> {
+        let global_config = &ctx.accounts.global_config;
+        let authority = ctx.accounts.authority.key();
+        let buyback = &mut ctx.accounts.buyback_config;
+        buyback.bump = ctx.bumps.buyback_config;
+
+        // Pause switch (pauser, immediate)
+        if let Some(paused) = args.is_paused {
+            require!(
+                global_config.has_role(Role::Pauser, authority),
+                PresaleError::Unauthorized
+            );
+            buyback.is_paused = paused;
+        }
+
+        // Budget spends treasury funds, like withdraw_treasury
+        if let Some(budget) = args.budget_lamports {
+            require_keys_eq!(global_config.admin, authority, PresaleError::Unauthorized);
+            require!(!global_config.multisig.is_active(), PresaleError::MultisigRequired);
+            buyback.budget_lamports = budget;
+        }
+
+        if let Some(burn_returned) = args.burn_returned {
+            require_keys_eq!(global_config.admin, authority, PresaleError::Unauthorized);
+            buyback.burn_returned = burn_returned;
+        }
+
+        emit!(BuybackConfigUpdated {
+            is_paused: buyback.is_paused,
+            budget_lamports: buyback.budget_lamports,
+            spent_lamports: buyback.spent_lamports,
+            burn_returned: buyback.burn_returned,
+        });
+
+        Ok(())
+    }
+
+    // -----------------------------------------
+    // 33) Admin: set the buyback floor price of a tier
+    // -----------------------------------------
+    // Capped at the sale price, so the treasury never pays more than it took in.
+    pub fn set_buyback_price(ctx: Context<SetBuybackPrice>, price_lamports: u64) -> Result<()> {
+        let tier = &mut ctx.accounts.tier;
+        require!(
+            price_lamports <= tier.price_lamports,
+            PresaleError::InvalidBuybackPrice
+        );
+
+        let old_price_lamports = tier.buyback_price_lamports;
+        tier.buyback_price_lamports = price_lamports;
+
+        emit!(BuybackPriceChanged {
+            tier_id: tier.id,
+            old_price_lamports,
+            new_price_lamports: price_lamports,
+        });
+
+        Ok(())
+    }
+
+    // -----------------------------------------
+    // 34) Holder: sell an NFT back to the program at the tier's floor price
+    // -----------------------------------------
+    pub fn sell_back_nft(ctx: Context<SellBackNft>) -> Result<()> {
+        let buyback = &ctx.accounts.buyback_config;
+        let tier = &ctx.accounts.tier;
+        // A later sale price cut also lowers the buyback price
+        let price = tier.buyback_price_lamports.min(tier.price_lamports);
+
+        // 1) Buyback open for this tier and within budget; grants and vouchers
+        //    were not paid on-chain, and nobody gets back more than they paid
+        require!(!buyback.is_paused, PresaleError::BuybackPaused);
+        require!(price > 0, PresaleError::BuybackNotOffered);
+        let paid = ctx.accounts.nft_record.paid_lamports;
+        require!(paid > 0, PresaleError::BuybackNotPaid);
+        let price = price.min(paid);
+        let spent = buyback
+            .spent_lamports
+            .checked_add(price)
+            .filter(|spent| *spent <= buyback.budget_lamports)
+            .ok_or(error!(PresaleError::BuybackBudgetExhausted))?;
+
+        // 2) NFT must be ours and belong to the passed tier
+        verify_presale_nft(
+            &ctx.accounts.nft_metadata,
+            ctx.accounts.nft_mint.key(),
+            &ctx.accounts.global_config,
+            ctx.accounts.global_config.key(),
+            &ctx.accounts.nft_record,
+            tier,
+        )?;
+
+        // 3) Take the NFT back: burn it, or park it in the buyback vault
+        let burned = buyback.burn_returned;
+        if burned {
+            let cpi_ctx_burn = CpiContext::new(
+                ctx.accounts.token_metadata_program.to_account_info(),
+                BurnNft {
+                    metadata: ctx.accounts.nft_metadata.to_account_info(),
+                    owner: ctx.accounts.seller.to_account_info(),
+                    mint: ctx.accounts.nft_mint.to_account_info(),
+                    token: ctx.accounts.nft_token_account.to_account_info(),
+                    edition: ctx.accounts.nft_edition.to_account_info(),
+                    spl_token: ctx.accounts.token_program.to_account_info(),
+                },
+            )
+            .with_remaining_accounts(vec![ctx.accounts.collection_metadata.to_account_info()]);
+            burn_nft(cpi_ctx_burn, Some(ctx.accounts.collection_metadata.key()))?;
+        } else {
+            let vault_token_account = ctx
+                .accounts
+                .vault_token_account
+                .as_ref()
+                .ok_or(error!(PresaleError::MissingBuybackVault))?;
+
+            // ATA program checks the address (owner = buyback vault PDA)
+            let cpi_ctx_ata = CpiContext::new(
+                ctx.accounts.associated_token_program.to_account_info(),
+                associated_token::Create {
+                    payer: ctx.accounts.seller.to_account_info(),
+                    associated_token: vault_token_account.to_account_info(),
+                    authority: ctx.accounts.buyback_vault.to_account_info(),
+                    mint: ctx.accounts.nft_mint.to_account_info(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                },
+            );
+            associated_token::create_idempotent(cpi_ctx_ata)?;
+
+            let cpi_ctx_transfer = CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.nft_token_account.to_account_info(),
+                    to: vault_token_account.to_account_info(),
+                    authority: ctx.accounts.seller.to_account_info(),
+                },
+            );
+            token::transfer(cpi_ctx_transfer, 1)?;
+        }
+
+        // 4) Pay the seller from the treasury
+        transfer_from_treasury(
+            &ctx.accounts.treasury,
+            &ctx.accounts.seller.to_account_info(),
+            &ctx.accounts.system_program,
+            ctx.bumps.treasury,
+            price,
+        )?;
+
+        ctx.accounts.buyback_config.spent_lamports = spent;
+        ctx.accounts.tier.supply_bought_back += 1;
+
+        emit!(NftBoughtBack {
+            nft_mint: ctx.accounts.nft_mint.key(),
+            seller: ctx.accounts.seller.key(),
+            tier_id: ctx.accounts.tier.id,
+            price_lamports: price,
+            burned,
+        });
+
+        Ok(())
+    }
+
+    // -----------------------------------------
+    // 35) Admin: set (timelocked) or clear with default() the backend voucher signer
+    // -----------------------------------------
+    pub fn set_voucher_signer(
+        ctx: Context<SetVoucherSigner>,
+        voucher_signer: Pubkey,
+    ) -> Result<()> {
+        let global_config = &mut ctx.accounts.global_config;
+
+        if voucher_signer == Pubkey::default() {
+            // Switching vouchers off only stops minting: apply it now and
+            // drop any signer still waiting
+            let old_signer = global_config.voucher_signer;
+            global_config.voucher_signer = voucher_signer;
+            global_config.pending_voucher_signer = None;
+            global_config.pending_voucher_signer_effective_at = 0;
+
+            emit!(VoucherSignerUpdated {
+                old_signer,
+                new_signer: voucher_signer,
+            });
+        } else {
+            // A new key can mint for free: with a multisig configured it goes
+            // through SetVoucherSigner, otherwise it waits for the timelock
+            require!(!global_config.multisig.is_active(), PresaleError::MultisigRequired);
+            global_config.queue_voucher_signer(voucher_signer)?;
+        }
+
+        Ok(())
+    }
+
+    // -----------------------------------------
+    // 36) Anyone: mint a tier paid off-chain, against a backend-signed voucher
+    // -----------------------------------------
+    // The instruction right before this one must be an ed25519 program
+    // verification of voucher_message(&voucher) by global_config.voucher_signer.
+    pub fn redeem_voucher(ctx: Context<RedeemVoucher>, voucher: Voucher) -> Result<()> {
+        let global_config_info = ctx.accounts.global_config.to_account_info();
+        let global_config = &ctx.accounts.global_config;
+
+        // 1) Voucher signed by our backend, unexpired, for this tier + recipient
+        //    (replay is stopped by the nonce PDA, created in accounts)
+        require_keys_neq!(
+            global_config.voucher_signer,
+            Pubkey::default(),
+            PresaleError::VouchersDisabled
+        );
+        check_voucher_signature(
+            &ctx.accounts.instructions,
+            &global_config.voucher_signer,
+            &voucher_message(&voucher)?,
+        )?;
+        let clock = Clock::get()?;
+        require!(
+            clock.unix_timestamp <= voucher.expires_at,
+            PresaleError::VoucherExpired
+        );
+
+        // 2) Same sale gates as mint_nft, except payment, anti-bot and
+        //    throttles; KYC / invite are checked by the backend before signing
+        require!(!global_config.is_sales_paused, PresaleError::SalesPaused);
+        let tier = &mut ctx.accounts.tier;
+        if let Some(change) = tier.apply_pending_if_due(clock.unix_timestamp) {
+            emit!(TierChangeApplied {
+                tier_id: tier.id,
+                change,
+            });
+        }
+        require!(!tier.is_paused, PresaleError::TierPaused);
+        require!(tier.supply_minted < tier.supply_total, PresaleError::TierSoldOut);
+
+        // 3) Mint 1 NFT to the recipient's ATA
+        let cpi_ctx_mint = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token::MintTo {
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.recipient_token_account.to_account_info(),
+                authority: ctx.accounts.payer.to_account_info(),
+            },
+        );
+        token::mint_to(cpi_ctx_mint, 1)?;
+
+        // 4) Serial + event + metadata, exactly like mint_nft
+        let collection_mint_key = global_config.collection_mint;
+        let tier = &mut ctx.accounts.tier;
+        let serial: u16 = tier.supply_minted + 1;
+
+        let design_key = resolve_design_key(tier, serial, voucher.design_choice)?;
+        let data = presale_nft_data(tier, serial, voucher.design_choice, ctx.accounts.admin.key())?;
+        emit_cpi!(NftMinted {
+            tier_id: tier.id,
+            serial,
+            design_key,
+            mint: ctx.accounts.mint.key(),
+            recipient: ctx.accounts.recipient.key(),
+            buyer: ctx.accounts.payer.key(),
+            price_paid: 0,
+            payment_mint: NATIVE_SOL_MINT,
+            mint_path: MintPath::Voucher,
+            uri: data.uri.clone(),
+            slot: clock.slot,
+            unix_timestamp: clock.unix_timestamp,
+        });
+
+        create_presale_nft_metadata(
+            &NftMetadataAccounts {
+                payer: ctx.accounts.payer.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                metadata: ctx.accounts.metadata.to_account_info(),
+                edition: ctx.accounts.edition.to_account_info(),
+                global_config: global_config_info,
+                collection_mint: ctx.accounts.collection_mint.to_account_info(),
+                collection_metadata: ctx.accounts.collection_metadata.to_account_info(),
+                collection_master_edition: ctx.accounts.collection_master_edition.to_account_info(),
+                token_metadata_program: ctx.accounts.token_metadata_program.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                rent: ctx.accounts.rent.to_account_info(),
+            },
+            data,
+            ctx.bumps.global_config,
+            collection_mint_key,
+        )?;
+
+        // 5) Per-NFT record, counters + used-nonce record
+        //    (vouchers are paid off-chain)
+        ctx.accounts.nft_record.set_inner(NftRecord {
+            mint: ctx.accounts.mint.key(),
+            tier_id: tier.id,
+            serial,
+            bump: ctx.bumps.nft_record,
+            paid_lamports: 0,
+        });
+        tier.supply_minted += 1;
+
+        let record = &mut ctx.accounts.voucher_record;
+        record.nonce = voucher.nonce;
+        record.bump = ctx.bumps.voucher_record;
+        record.tier_id = tier.id;
+        record.recipient = voucher.recipient;
+        record.mint = ctx.accounts.mint.key();
+        record.redeemed_at = clock.unix_timestamp;
+
+        emit!(VoucherRedeemed {
+            nonce: voucher.nonce,
+            tier_id: tier.id,
+            serial,
+            mint: ctx.accounts.mint.key(),
+            recipient: voucher.recipient,
+        });
+
+        Ok(())
+    }
+
+    // -----------------------------------------
+    // 37) KYC manager: write (or refresh) a wallet's KYC registry entry
+    // -----------------------------------------
+    pub fn set_kyc_record(
+        ctx: Context<SetKycRecord>,
+        wallet: Pubkey,
+        args: SetKycRecordArgs,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(args.expires_at > now, PresaleError::InvalidKycRecord);
+        require!(
+            args.country_code.iter().all(u8::is_ascii_uppercase),
+            PresaleError::InvalidKycRecord
+        );
+
+        let record = &mut ctx.accounts.kyc_record;
+        if record.wallet == Pubkey::default() {
+            record.wallet = wallet;
+            record.bump = ctx.bumps.kyc_record;
+            record.verified_at = now;
+        }
+        record.level = args.level;
+        record.expires_at = args.expires_at;
+        record.country_code = args.country_code;
+        record.updated_at = now;
+
+        emit!(KycRecordUpdated {
+            wallet,
+            level: args.level,
+            expires_at: args.expires_at,
+            country_code: args.country_code,
+            operator: ctx.accounts.authority.key(),
+        });
+
+        Ok(())
+    }
+
+    // -----------------------------------------
+    // 38) KYC manager: revoke a wallet's KYC entry (closes the PDA)
+    // -----------------------------------------
+    pub fn revoke_kyc_record(ctx: Context<RevokeKycRecord>, wallet: Pubkey) -> Result<()> {
+        emit!(KycRecordRevoked {
+            wallet,
+            operator: ctx.accounts.authority.key(),
+        });
+
+        Ok(())
+    }
+
+    // -----------------------------------------
+    // 39) Admin: backfill a tier PDA created before its newer fields existed
+    // -----------------------------------------
+    // Values are explicit: the old layout has nothing to derive them from.
+    pub fn migrate_tier(
+        ctx: Context<MigrateTier>,
+        admin_quota: u16,
+        supply_max: u16,
+    ) -> Result<()> {
+        let tier = &mut ctx.accounts.tier;
+
+        let from_version = tier.version;
+        require!(
+            from_version < TIER_CONFIG_VERSION,
+            PresaleError::TierAlreadyMigrated
+        );
+        require!(
+            admin_quota <= tier.supply_total && supply_max >= tier.supply_total,
+            PresaleError::InvalidTierConfig
+        );
+
+        // v0 -> v1: admin_quota and supply_max read as 0, i.e. no admin mints
+        // and no supply change at all
+        tier.admin_quota = admin_quota;
+        tier.supply_max = supply_max;
+
+        tier.version = TIER_CONFIG_VERSION;
+
+        emit!(TierMigrated {
+            tier_id: tier.id,
+            from_version,
+            to_version: TIER_CONFIG_VERSION,
+            admin_quota,
+            supply_max,
+        });
+
+        Ok(())
+    }
+
+    // -----------------------------------------
+    // 40) Admin: write the NftRecord of an NFT minted before records existed
+    // -----------------------------------------
+    // The metadata uri must be exactly what build_uri produced for the serial.
+    // `paid_lamports` is what its mint transaction paid (0 for admin mints).
+    pub fn register_nft_record(
+        ctx: Context<RegisterNftRecord>,
+        serial: u16,
+        design_choice: Option<u8>,
+        paid_lamports: u64,
+    ) -> Result<()> {
+        let tier = &ctx.accounts.tier;
+        require!(
+            serial >= 1 && serial <= tier.supply_minted,
+            PresaleError::InvalidPresaleNft
+        );
+
+        let metadata = &ctx.accounts.nft_metadata;
+        verify_presale_metadata(
+            metadata,
+            ctx.accounts.nft_mint.key(),
+            &ctx.accounts.global_config,
+            ctx.accounts.global_config.key(),
+        )?;
+        require!(
+            metadata.uri.trim_end_matches('\0') == build_uri(tier, serial, design_choice)?,
+            PresaleError::InvalidPresaleNft
+        );
+
+        ctx.accounts.nft_record.set_inner(NftRecord {
+            mint: ctx.accounts.nft_mint.key(),
+            tier_id: tier.id,
+            serial,
+            bump: ctx.bumps.nft_record,
+            paid_lamports,
+        });
+
+        emit!(NftRecordRegistered {
+            mint: ctx.accounts.nft_mint.key(),
+            tier_id: tier.id,
+            serial,
+            paid_lamports,
+        });
+
+        Ok(())
+    }
+
+    // -----------------------------------------
+    // 41) Admin: complete a Shipped redemption the holder never confirmed
+    // -----------------------------------------
+    // After REDEMPTION_CONFIRM_TIMEOUT_SECONDS the redemption authority burns
+    // the token as its delegate (metadata and edition stay, owned by Metaplex).
+    pub fn complete_overdue_redemption(ctx: Context<CompleteOverdueRedemption>) -> Result<()> {
+        let redemption = &ctx.accounts.redemption;
+        require!(
+            redemption.status == RedemptionStatus::Shipped,
+            PresaleError::InvalidRedemptionStatus
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            now >= redemption
+                .updated_at
+                .saturating_add(REDEMPTION_CONFIRM_TIMEOUT_SECONDS),
+            PresaleError::RedemptionNotOverdue
+        );
+
+        // 1) Thaw, then burn the token with the delegate's allowance
+        let signer_seeds: &[&[u8]] = &[
+            REDEMPTION_AUTHORITY_SEED,
+            &[ctx.bumps.redemption_authority],
+        ];
+        let signer: &[&[&[u8]]] = &[signer_seeds];
+
+        let cpi_ctx_thaw = CpiContext::new_with_signer(
+            ctx.accounts.token_metadata_program.to_account_info(),
+            ThawDelegatedAccount {
+                metadata: ctx.accounts.nft_metadata.to_account_info(),
+                delegate: ctx.accounts.redemption_authority.to_account_info(),
+                token_account: ctx.accounts.nft_token_account.to_account_info(),
+                edition: ctx.accounts.nft_edition.to_account_info(),
+                mint: ctx.accounts.nft_mint.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
+            signer,
+        );
+        thaw_delegated_account(cpi_ctx_thaw)?;
+
+        let cpi_ctx_burn = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            token::Burn {
+                mint: ctx.accounts.nft_mint.to_account_info(),
+                from: ctx.accounts.nft_token_account.to_account_info(),
+                authority: ctx.accounts.redemption_authority.to_account_info(),
+            },
+            signer,
+        );
+        token::burn(cpi_ctx_burn, 1)?;
+
+        // 2) Same bookkeeping as complete_redemption
+        let redemption = &mut ctx.accounts.redemption;
+        redemption.status = RedemptionStatus::Completed;
+        redemption.updated_at = now;
+        ctx.accounts.tier.supply_redeemed += 1;
+
+        emit!(RedemptionCompleted {
+            nft_mint: redemption.nft_mint,
+            owner: redemption.owner,
+            tier_id: redemption.tier_id,
+        });
+
+        Ok(())
+    }
+
+    // -----------------------------------------
+    // 42) Admin: hand a bought-back NFT out of the buyback vault
+    // -----------------------------------------
+    // E.g. to the wallet re-selling it; with a multisig configured this goes
+    // through a ReleaseBuybackNft proposal.
+    pub fn release_buyback_nft(ctx: Context<ReleaseBuybackNft>) -> Result<()> {
+        require!(
+            !ctx.accounts.global_config.multisig.is_active(),
+            PresaleError::MultisigRequired
+        );
+
+        release_from_buyback_vault(
+            &ctx.accounts.vault_token_account,
+            &ctx.accounts.destination_token_account,
+            &ctx.accounts.buyback_vault.to_account_info(),
+            &ctx.accounts.treasury.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+            ctx.bumps.buyback_vault,
+        )?;
+
+        emit!(BuybackNftReleased {
+            nft_mint: ctx.accounts.nft_mint.key(),
+            destination: ctx.accounts.destination_token_account.owner,
+        });
+
+        Ok(())
+    }
+}
+
+// ---------------------------------------------
+// Tier identifiers (must match frontend JSON)
+// ---------------------------------------------
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum TierId {
+    TreeSteel = 0,
+    Bronze = 1,
+    Silver = 2,
+    Gold = 3,
+    Platinum = 4,
+    Ws20 = 5,
+}
+
+impl TierId {
+    pub const ALL: [TierId; 6] = [
+        TierId::TreeSteel,
+        TierId::Bronze,
+        TierId::Silver,
+        TierId::Gold,
+        TierId::Platinum,
+        TierId::Ws20,
+    ];
+
+    pub fn as_index(self) -> usize {
+        self as usize
+    }
+}
+
+// ---------------------------------------------
+// Tier configuration stored on-chain (one PDA per tier id)
+// ---------------------------------------------
+pub const TIER_SEED: &[u8] = b"vigri-presale-tier";
+
+// "VIGRI " + label + " NFT #" + 6-digit serial is 18 chars + label,
+// which must fit Metaplex's 32-char name
+pub const MAX_TIER_LABEL_LEN: usize = 14;
+// base + "/TR/" + "000000.json" must fit Metaplex's 200-char uri
+pub const MAX_TIER_URI_BASE_LEN: usize = 128;
+
+pub const TIER_CONFIG_SPACE: usize = 8 + TierConfig::INIT_SPACE;
+
+// Current TierConfig version. Tiers created before a field existed read it as
+// zero and stay at an older version until migrate_tier backfills them.
+// v0 -> v1: admin_quota, supply_max
+pub const TIER_CONFIG_VERSION: u8 = 1;
+
+// Fields after `uri_base` were added over time by taking bytes from
+// `reserved`, so existing tier PDAs never need a realloc. `pending` sits in
+// front of them, so its layout is frozen (see PendingTierChange).
+#[account]
+#[derive(InitSpace)]
+pub struct TierConfig {
+    pub id: u8,               // tier id, part of the PDA seeds
+    pub bump: u8,             // PDA bump
+    pub supply_total: u16,    // max allowed supply
+    pub supply_minted: u16,   // current mint count
+    pub admin_minted: u16,    // minted via admin_mint
+    pub price_lamports: u64,  // price in lamports
+    pub kyc_required: bool,   // true for Silver+, WS20
+    pub invite_only: bool,    // true for WS20
+    pub transferable: bool,   // false for WS20 (soulbound)
+    pub design_variants: u16, // 0 = design key follows serial, N = serial cycles 1..N
+    #[max_len(MAX_TIER_LABEL_LEN)]
+    pub label: String,        // wallet-facing label, e.g. "Bronze"
+    #[max_len(MAX_TIER_URI_BASE_LEN)]
+    pub uri_base: String,     // metadata folder, e.g. ".../nft/bronze/CU"
+    pub pending: PendingTierChange, // timelocked change, see apply_pending_if_due
+    pub admin_quota: u16,     // max NFTs via admin_mint / admin_airdrop
+    pub supply_max: u16,      // hard cap for supply_total, fixed at create_tier
+    pub supply_locked: bool,  // one-way: supply_total can no longer increase
+    pub is_paused: bool,      // per-tier sales pause, on top of is_sales_paused
+    pub anti_bot_max_mints: u8, // 0 = off, N = top-level mint_nft only, max N per tx
+    pub rate_limited: bool,   // mint_nft must pass this tier's RateLimiter
+    pub supply_burned: u16,   // NFTs of this tier burned by upgrade_nft
+    pub stake_weight: u16,    // staking points per second staked
+    pub supply_redeemed: u16, // NFTs of this tier burned for a physical item
+    pub buyback_price_lamports: u64, // treasury pays this per NFT sold back, 0 = no buyback
+    pub supply_bought_back: u16, // NFTs of this tier sold back to the program
+    pub version: u8,          // layout version, see TIER_CONFIG_VERSION
+    pub pending_stake_weight: Option<u16>, // part of the pending change
+    pub pending_supply_total: Option<u16>, // part of the pending change
+    pub reserved: [u8; 1],    // future flags / counters (do not touch now)
+}
+
+impl TierConfig {
+    pub fn new(args: CreateTierArgs, bump: u8) -> Self {
+        Self {
+            id: args.tier_id,
+            bump,
+            supply_total: args.supply_total,
+            supply_minted: 0,
+            admin_minted: 0,
+            price_lamports: args.price_lamports,
+            kyc_required: args.kyc_required,
+            invite_only: args.invite_only,
+            transferable: args.transferable,
+            design_variants: args.design_variants,
+            label: args.label,
+            uri_base: args.uri_base,
+            pending: PendingTierChange::default(),
+            admin_quota: args.admin_quota,
+            supply_max: args.supply_max,
+            supply_locked: false,
+            is_paused: false,
+            anti_bot_max_mints: 0,
+            rate_limited: false,
+            supply_burned: 0,
+            stake_weight: args.stake_weight,
+            supply_redeemed: 0,
+            buyback_price_lamports: 0,
+            supply_bought_back: 0,
+            version: TIER_CONFIG_VERSION,
+            pending_stake_weight: None,
+            pending_supply_total: None,
+            reserved: [0; 1],
+        }
+    }
+
+    // Tier PDA for a v0 inline entry: the launch preset (label, uri, quota)
+    // with the stored counters, price and flags carried over
+    pub fn from_v0(v0: &V0TierConfig, tier: TierId, bump: u8) -> Self {
+        let preset = CreateTierArgs::for_tier(tier);
+        let admin_quota = preset.admin_quota.min(v0.supply_total);
+        let supply_max = preset.supply_max.max(v0.supply_total);
+
+        let mut config = Self::new(
+            CreateTierArgs {
+                supply_total: v0.supply_total,
+                price_lamports: v0.price_lamports,
+                kyc_required: v0.kyc_required,
+                invite_only: v0.invite_only,
+                transferable: v0.transferable,
+                admin_quota,
+                supply_max,
+                ..preset
+            },
+            bump,
+        );
+        config.supply_minted = v0.supply_minted;
+        config.admin_minted = v0.admin_minted;
+        config
+    }
+
+    // The queued change, from `pending` and the pending_* fields added after it
+    pub fn pending_change(&self) -> TierChange {
+        TierChange {
+            price_lamports: self.pending.price_lamports,
+            kyc_required: self.pending.kyc_required,
+            invite_only: self.pending.invite_only,
+            transferable: self.pending.transferable,
+            admin_quota: self.pending.admin_quota,
+            supply_total: self.pending_supply_total,
+            stake_weight: self.pending_stake_weight,
+            effective_at: self.pending.effective_at,
+        }
+    }
+
+    fn set_pending_change(&mut self, change: TierChange) {
+        self.pending = PendingTierChange {
+            price_lamports: change.price_lamports,
+            kyc_required: change.kyc_required,
+            invite_only: change.invite_only,
+            transferable: change.transferable,
+            effective_at: change.effective_at,
+            admin_quota: change.admin_quota,
+        };
+        self.pending_stake_weight = change.stake_weight;
+        self.pending_supply_total = change.supply_total;
+    }
+
+    // Merges `change` into the pending one field by field: a queued value is
+    // only replaced by a new value for the same field, and the merged change
+    // waits for the later of both effective times
+    pub fn queue_change(&mut self, change: TierChange) {
+        let pending = self.pending_change();
+        let merged = if pending.is_empty() {
+            change
+        } else {
+            TierChange {
+                price_lamports: change.price_lamports.or(pending.price_lamports),
+                kyc_required: change.kyc_required.or(pending.kyc_required),
+                invite_only: change.invite_only.or(pending.invite_only),
+                transferable: change.transferable.or(pending.transferable),
+                admin_quota: change.admin_quota.or(pending.admin_quota),
+                supply_total: change.supply_total.or(pending.supply_total),
+                stake_weight: change.stake_weight.or(pending.stake_weight),
+                effective_at: change.effective_at.max(pending.effective_at),
+            }
+        };
+        self.set_pending_change(merged);
+
+        emit!(TierChangeQueued {
+            tier_id: self.id,
+            change: merged,
+        });
+    }
+
+    // Applies and clears the pending change once effective_at has passed
+    pub fn apply_pending_if_due(&mut self, now: i64) -> Option<TierChange> {
+        let change = self.pending_change();
+        if change.is_empty() || change.effective_at > now {
+            return None;
+        }
+
+        if let Some(price) = change.price_lamports {
+            emit!(TierPriceChanged {
+                tier_id: self.id,
+                old_price_lamports: self.price_lamports,
+                new_price_lamports: price,
+            });
+            self.price_lamports = price;
+        }
+        if let Some(kyc) = change.kyc_required {
+            self.kyc_required = kyc;
+        }
+        if let Some(invite_only) = change.invite_only {
+            self.invite_only = invite_only;
+        }
+        if let Some(transferable) = change.transferable {
+            self.transferable = transferable;
+        }
+        if let Some(admin_quota) = change.admin_quota {
+            self.admin_quota = admin_quota;
+        }
+        if let Some(supply_total) = change.supply_total {
+            // Re-check against the current state: mints or a supply lock may
+            // have happened since the change was queued.
+            let mut supply_total = supply_total.max(self.supply_minted);
+            if self.supply_locked {
+                supply_total = supply_total.min(self.supply_total);
+            }
+            emit!(TierSupplyChanged {
+                tier_id: self.id,
+                old_supply_total: self.supply_total,
+                new_supply_total: supply_total,
+            });
+            self.supply_total = supply_total;
+        }
+        // The supply lock may have kept supply_total below the queued quota
+        self.admin_quota = self.admin_quota.min(self.supply_total);
+        if let Some(stake_weight) = change.stake_weight {
+            self.stake_weight = stake_weight;
+        }
+        if change.kyc_required.is_some()
+            || change.invite_only.is_some()
+            || change.transferable.is_some()
+        {
+            self.emit_flags_changed();
+        }
+
+        self.set_pending_change(TierChange::default());
+        Some(change)
+    }
+
+    // Full flag set after a change, so indexers don't need the previous state
+    fn emit_flags_changed(&self) {
+        emit!(TierFlagsChanged {
+            tier_id: self.id,
+            kyc_required: self.kyc_required,
+            invite_only: self.invite_only,
+            transferable: self.transferable,
+            supply_locked: self.supply_locked,
+            anti_bot_max_mints: self.anti_bot_max_mints,
+        });
+    }
+}
+
+// Stored inside TierConfig ahead of later fields, so its layout is frozen:
+// newer pending values live in TierConfig (see TierConfig::pending_change)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct PendingTierChange {
+    pub price_lamports: Option<u64>,
+    pub kyc_required: Option<bool>,
+    pub invite_only: Option<bool>,
+    pub transferable: Option<bool>,
+    pub effective_at: i64,
+    pub admin_quota: Option<u16>, // after effective_at so older pending bytes keep their meaning
+}
+
+// A timelocked tier change as queued and applied (events carry this)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct TierChange {
+    pub price_lamports: Option<u64>,
+    pub kyc_required: Option<bool>,
+    pub invite_only: Option<bool>,
+    pub transferable: Option<bool>,
+    pub admin_quota: Option<u16>,
+    pub supply_total: Option<u16>,
+    pub stake_weight: Option<u16>,
+    pub effective_at: i64,
+}
+
+impl TierChange {
+    pub fn is_empty(&self) -> bool {
+        self.price_lamports.is_none()
+            && self.kyc_required.is_none()
+            && self.invite_only.is_none()
+            && self.transferable.is_none()
+            && self.admin_quota.is_none()
+            && self.supply_total.is_none()
+            && self.stake_weight.is_none()
+    }
+}
+
+impl CreateTierArgs {
+    // Launch presets (kept here so scripts and clients share the same numbers)
+    pub fn for_tier(tier: TierId) -> Self {
+        match tier {
+            TierId::TreeSteel => Self {
+                tier_id: TierId::TreeSteel as u8,
+                supply_total: 2000,
+                price_lamports: 500_000_000, // 0.5 SOL
+                kyc_required: false,
+                invite_only: false,
+                transferable: true,
+                design_variants: 0, // chosen by buyer (TR / FE)
+                label: "Tree/Steel".to_string(),
+                uri_base: "https://vigri.ee/metadata/nft/tree-steel".to_string(),
+                admin_quota: 100, // 5%
+                supply_max: 2000, // no second batch planned yet
+                stake_weight: 0, // set per tier once staking opens
+            },
+            TierId::Bronze => Self {
+                tier_id: TierId::Bronze as u8,
+                supply_total: 1000,
+                price_lamports: 2_000_000_000, // 2 SOL
+                kyc_required: false,
+                invite_only: false,
+                transferable: true,
+                design_variants: 1, // CU: single design
+                label: "Bronze".to_string(),
+                uri_base: "https://vigri.ee/metadata/nft/bronze/CU".to_string(),
+                admin_quota: 50, // 5%
+                supply_max: 1000, // no second batch planned yet
+                stake_weight: 0, // set per tier once staking opens
+            },
+            TierId::Silver => Self {
+                tier_id: TierId::Silver as u8,
+                supply_total: 200,
+                price_lamports: 10_000_000_000, // 10 SOL
+                kyc_required: true,
+                invite_only: false,
+                transferable: true,
+                design_variants: 10, // AG: 1..10
+                label: "Silver".to_string(),
+                uri_base: "https://vigri.ee/metadata/nft/silver/AG".to_string(),
+                admin_quota: 10, // 5%
+                supply_max: 200, // no second batch planned yet
+                stake_weight: 0, // set per tier once staking opens
+            },
+            TierId::Gold => Self {
+                tier_id: TierId::Gold as u8,
+                supply_total: 100,
+                price_lamports: 40_000_000_000, // 40 SOL
+                kyc_required: true,
+                invite_only: false,
+                transferable: true,
+                design_variants: 0, // AU: unique per serial
+                label: "Gold".to_string(),
+                uri_base: "https://vigri.ee/metadata/nft/gold/AU".to_string(),
+                admin_quota: 5, // 5%
+                supply_max: 100, // no second batch planned yet
+                stake_weight: 0, // set per tier once staking opens
+            },
+            TierId::Platinum => Self {
+                tier_id: TierId::Platinum as u8,
+                supply_total: 20,
+                price_lamports: 80_000_000_000, // 80 SOL
+                kyc_required: true,
+                invite_only: false,
+                transferable: true,
+                design_variants: 0, // PT: unique per serial
+                label: "Platinum".to_string(),
+                uri_base: "https://vigri.ee/metadata/nft/platinum/PT".to_string(),
+                admin_quota: 1, // 5%
+                supply_max: 20, // no second batch planned yet
+                stake_weight: 0, // set per tier once staking opens
+            },
+            TierId::Ws20 => Self {
+                tier_id: TierId::Ws20 as u8,
+                supply_total: 20,
+                price_lamports: 0, // 0 SOL
+                kyc_required: true,
+                invite_only: true,
+                transferable: false,
+                design_variants: 0, // WS: unique per serial
+                label: "WS-20".to_string(),
+                uri_base: "https://vigri.ee/metadata/nft/ws/WS".to_string(),
+                admin_quota: 20, // all WS-20 NFTs are granted
+                supply_max: 20, // no second batch planned yet
+                stake_weight: 0, // set per tier once staking opens
+            },
+        }
+    }
+}
+
+// ---------------------------------------------
+// Global configuration (PDA)
+// ---------------------------------------------
+pub const PLACEHOLDER_NAME: &str = "VIGRI Mystery NFT";
+pub const PLACEHOLDER_SYMBOL: &str = "VIGRINFT";
+pub const PLACEHOLDER_URI: &str = "https://vigri.ee/metadata/nft/vigri-mystery.json";
+
+// Final PDA seed for the presale global config
+pub const GLOBAL_CONFIG_SEED: &[u8] = b"vigri-presale-config";
+
+// Current GlobalConfig layout version (bump together with a migrate_config step)
+pub const GLOBAL_CONFIG_VERSION: u8 = 9;
+
+// Exact space for the current layout; older accounts are resized by migrate_config
+pub const GLOBAL_CONFIG_SPACE: usize = 8 + GlobalConfig::INIT_SPACE;
+
+// Byte offset of `version`: discriminator + 3 pubkeys + pause flag.
+// The fields before it must never move, migrate_config reads them raw.
+pub const GLOBAL_CONFIG_VERSION_OFFSET: usize = 8 + 32 * 3 + 1;
+
+// v0 stored one inline tier entry per TierId at GLOBAL_CONFIG_VERSION_OFFSET
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct V0TierConfig {
+    pub id: u8,
+    pub supply_total: u16,
+    pub supply_minted: u16,
+    pub admin_minted: u16,
+    pub price_lamports: u64,
+    pub kyc_required: bool,
+    pub invite_only: bool,
+    pub transferable: bool,
+    pub reserved: [u8; 8],
+}
+
+pub fn read_v0_tiers(data: &[u8]) -> Result<[V0TierConfig; 6]> {
+    let mut bytes = data
+        .get(GLOBAL_CONFIG_VERSION_OFFSET..)
+        .ok_or(error!(PresaleError::InvalidConfigAccount))?;
+
+    let mut tiers = [V0TierConfig::default(); 6];
+    for tier in tiers.iter_mut() {
+        *tier = V0TierConfig::deserialize(&mut bytes)
+            .map_err(|_| error!(PresaleError::InvalidConfigAccount))?;
+    }
+
+    Ok(tiers)
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct GlobalConfig {
+    pub admin: Pubkey,            // authority of the program
+    pub collection_mint: Pubkey,  // main Metaplex collection mint
+    pub payment_mint: Pubkey,     // for future SPL payments (v1 can ignore)
+    pub is_sales_paused: bool,    // global pause switch
+    pub version: u8,              // layout version, see GLOBAL_CONFIG_VERSION
+    pub pending_admin: Pubkey,    // proposed admin, default() = no transfer pending
+    pub roles: RoleKeys,          // delegated operator keys (admin holds every role)
+    pub multisig: MultisigConfig, // signers guarding collection / treasury changes
+    pub config_delay_seconds: i64, // min delay for timelocked changes
+    pub pending_collection_mint: Pubkey, // queued collection mint, default() = none
+    pub pending_collection_effective_at: i64,
+    pub rate_limited: bool,       // mint_nft must pass the global RateLimiter
+    pub voucher_signer: Pubkey,   // backend key signing vouchers, default() = vouchers off
+    pub pending_config_delay: Option<i64>, // queued shorter config_delay_seconds
+    pub pending_config_delay_effective_at: i64,
+    pub pending_voucher_signer: Option<Pubkey>, // queued voucher_signer
+    pub pending_voucher_signer_effective_at: i64,
+    pub reserved: [u8; 64],       // future use, keep zeroed
+}
+
+impl GlobalConfig {
+    // The admin implicitly holds every role; default() keys are never matched
+    pub fn has_role(&self, role: Role, key: Pubkey) -> bool {
+        let role_key = self.roles.key(role);
+        key == self.admin || (role_key != Pubkey::default() && role_key == key)
+    }
+
+    // Earliest allowed effective time is now + config_delay_seconds
+    pub fn resolve_effective_at(&self, requested: Option<i64>) -> Result<i64> {
+        let earliest = Clock::get()?.unix_timestamp + self.config_delay_seconds;
+        let effective_at = requested.unwrap_or(earliest);
+        require!(effective_at >= earliest, PresaleError::InvalidConfigDelay);
+        Ok(effective_at)
+    }
+
+    pub fn queue_collection_mint(
+        &mut self,
+        collection_mint: Pubkey,
+        effective_at: Option<i64>,
+    ) -> Result<()> {
+        self.pending_collection_mint = collection_mint;
+        self.pending_collection_effective_at = self.resolve_effective_at(effective_at)?;
+
+        emit!(CollectionMintChangeQueued {
+            collection_mint,
+            effective_at: self.pending_collection_effective_at,
+        });
+
+        Ok(())
+    }
+
+    pub fn queue_voucher_signer(&mut self, voucher_signer: Pubkey) -> Result<()> {
+        self.pending_voucher_signer = Some(voucher_signer);
+        self.pending_voucher_signer_effective_at = self.resolve_effective_at(None)?;
+
+        emit!(VoucherSignerChangeQueued {
+            voucher_signer,
+            effective_at: self.pending_voucher_signer_effective_at,
+        });
+
+        Ok(())
+    }
+}
+
+// Timelock defaults for price / flag / collection changes
+pub const DEFAULT_CONFIG_DELAY_SECONDS: i64 = 24 * 60 * 60; // 24h
+pub const MAX_CONFIG_DELAY_SECONDS: i64 = 30 * 24 * 60 * 60; // 30 days
+
+// Treasury PDA (system account) receiving public mint payments
+pub const TREASURY_SEED: &[u8] = b"vigri-presale-treasury";
+
+// Move lamports out of the treasury PDA, keeping it rent-exempt
+fn transfer_from_treasury<'info>(
+    treasury: &SystemAccount<'info>,
+    destination: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    treasury_bump: u8,
+    amount: u64,
+) -> Result<()> {
+    let rent_floor = Rent::get()?.minimum_balance(0);
+    let available = treasury.lamports().saturating_sub(rent_floor);
+    require!(amount > 0 && amount <= available, PresaleError::InsufficientTreasury);
+
+    let signer_seeds: &[&[u8]] = &[TREASURY_SEED, &[treasury_bump]];
+    let signer: &[&[&[u8]]] = &[signer_seeds];
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        system_program.to_account_info(),
+        system_program::Transfer {
+            from: treasury.to_account_info(),
+            to: destination.clone(),
+        },
+        signer,
+    );
+    system_program::transfer(cpi_ctx, amount)
+}
+
+// ---------------------------------------------
+// Built-in multisig (stored in GlobalConfig, proposals are PDAs)
+// ---------------------------------------------
+pub const MAX_MULTISIG_SIGNERS: usize = 5;
+
+pub const PROPOSAL_SEED: &[u8] = b"vigri-presale-proposal";
+
+pub const PROPOSAL_SPACE: usize = 8 + Proposal::INIT_SPACE;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct MultisigConfig {
+    pub signers: [Pubkey; MAX_MULTISIG_SIGNERS], // first `signer_count` entries are used
+    pub signer_count: u8,
+    pub threshold: u8,        // 0 = multisig disabled
+    pub signers_epoch: u32,   // bumped on every signer change, invalidates old proposals
+    pub proposal_count: u64,  // next proposal id
+}
+
+impl MultisigConfig {
+    pub fn is_active(&self) -> bool {
+        self.threshold > 0
+    }
+
+    pub fn signer_index(&self, key: Pubkey) -> Option<usize> {
+        self.signers[..self.signer_count as usize]
+            .iter()
+            .position(|signer| *signer == key)
+    }
+
+    pub fn is_signer(&self, key: Pubkey) -> bool {
+        self.signer_index(key).is_some()
+    }
+
+    pub fn replace_signers(&mut self, signers: &[Pubkey], threshold: u8) -> Result<()> {
+        require!(
+            !signers.is_empty() && signers.len() <= MAX_MULTISIG_SIGNERS,
+            PresaleError::InvalidMultisig
+        );
+        require!(
+            threshold > 0 && threshold as usize <= signers.len(),
+            PresaleError::InvalidMultisig
+        );
+        for (i, signer) in signers.iter().enumerate() {
+            require!(
+                *signer != Pubkey::default() && !signers[..i].contains(signer),
+                PresaleError::InvalidMultisig
+            );
+        }
+
+        self.signers = [Pubkey::default(); MAX_MULTISIG_SIGNERS];
+        self.signers[..signers.len()].copy_from_slice(signers);
+        self.signer_count = signers.len() as u8;
+        self.threshold = threshold;
+        self.signers_epoch += 1;
+
+        Ok(())
+    }
+
+    pub fn disable(&mut self) {
+        self.signers = [Pubkey::default(); MAX_MULTISIG_SIGNERS];
+        self.signer_count = 0;
+        self.threshold = 0;
+        self.signers_epoch += 1;
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub enum ProposedAction {
+    SetTierPrice {
+        tier_id: u8,
+        price_lamports: u64,
+    },
+    SetCollectionMint {
+        collection_mint: Pubkey,
+    },
+    WithdrawTreasury {
+        destination: Pubkey,
+        amount: u64,
+    },
+    // signer_count 0 with threshold 0 disables the multisig
+    SetMultisig {
+        signers: [Pubkey; MAX_MULTISIG_SIGNERS],
+        signer_count: u8,
+        threshold: u8,
+    },
+    SetBuybackBudget {
+        budget_lamports: u64,
+    },
+    // destination = owner of the token account receiving the NFT
+    ReleaseBuybackNft {
+        nft_mint: Pubkey,
+        destination: Pubkey,
+    },
+    SetVoucherSigner {
+        voucher_signer: Pubkey,
+    },
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Proposal {
+    pub id: u64,
+    pub bump: u8,
+    pub proposer: Pubkey,
+    pub action: ProposedAction,
+    pub signers_epoch: u32,   // must match MultisigConfig::signers_epoch
+    pub approvals: u8,        // bit i = multisig.signers[i] approved
+    pub executed: bool,
+    pub created_at: i64,
+}
+
+// ---------------------------------------------
+// Mint throttles (global + per tier RateLimiter PDAs)
+// ---------------------------------------------
+pub const RATE_LIMIT_SEED: &[u8] = b"vigri-presale-rate-limit";
+
+// Scope of the global limiter; tier ids never use it (checked in create_tier)
+pub const GLOBAL_RATE_LIMIT_SCOPE: u8 = u8::MAX;
+
+// Ring buffer capacity, i.e. the largest allowed max_mints
+pub const MAX_RATE_LIMIT_MINTS: usize = 32;
+
+pub const RATE_LIMITER_SPACE: usize = 8 + RateLimiter::INIT_SPACE;
+
+#[account]
+#[derive(InitSpace)]
+pub struct RateLimiter {
+    pub scope: u8,               // tier id or GLOBAL_RATE_LIMIT_SCOPE, part of the PDA seeds
+    pub bump: u8,
+    pub max_mints: u16,          // allowed mints per window (0 = off)
+    pub window_slots: u64,       // window length in slots
+    pub head: u16,               // oldest entry of the ring, overwritten next
+    pub recent_slots: [u64; MAX_RATE_LIMIT_MINTS], // slots of the last max_mints mints (0 = empty)
+}
+
+impl RateLimiter {
+    // New settings start with an empty window
+    pub fn configure(&mut self, max_mints: u16, window_slots: u64) {
+        self.max_mints = max_mints;
+        self.window_slots = window_slots;
+        self.head = 0;
+        self.recent_slots = [0; MAX_RATE_LIMIT_MINTS];
+    }
+
+    // The ring holds the slots of the last `max_mints` mints. A new mint is
+    // allowed only if the oldest of them has left the window.
+    pub fn record_mint(&mut self, slot: u64) -> Result<()> {
+        if self.max_mints == 0 {
+            return Ok(());
+        }
+
+        let head = self.head as usize;
+        let oldest = self.recent_slots[head];
+        require!(
+            oldest == 0 || slot.saturating_sub(oldest) >= self.window_slots,
+            PresaleError::RateLimited
+        );
+
+        self.recent_slots[head] = slot;
+        self.head = ((head + 1) % self.max_mints as usize) as u16;
+        Ok(())
+    }
+}
+
+// ---------------------------------------------
+// Token allocations for NFT holders
+// ---------------------------------------------
+pub const ALLOCATION_SEED: &[u8] = b"vigri-presale-allocation";
+pub const ALLOCATION_VAULT_SEED: &[u8] = b"vigri-presale-vault";
+pub const CLAIM_SEED: &[u8] = b"vigri-presale-claim";
+
+pub const TIER_ALLOCATION_SPACE: usize = 8 + TierAllocation::INIT_SPACE;
+pub const CLAIM_RECORD_SPACE: usize = 8 + ClaimRecord::INIT_SPACE;
+
+// Per-tier entitlement: every NFT of the tier vests amount_per_nft.
+// Nothing unlocks before vesting_start_ts + cliff_seconds, then the amount
+// unlocks linearly over vesting_seconds (0 = everything at the cliff).
+#[account]
+#[derive(InitSpace)]
+pub struct TierAllocation {
+    pub tier_id: u8,             // part of the PDA seeds
+    pub bump: u8,
+    pub token_mint: Pubkey,      // token paid out from the [ALLOCATION_VAULT_SEED, mint] vault
+    pub amount_per_nft: u64,     // base units per NFT (0 = claims closed)
+    pub total_claimed: u64,      // paid out so far
+    pub vesting_start_ts: i64,   // schedule start
+    pub cliff_seconds: i64,      // locked period after start
+    pub vesting_seconds: i64,    // linear release after the cliff
+    pub reserved: [u8; 8],       // future use, keep zeroed
+}
+
+impl TierAllocation {
+    // Unlocked part of amount_per_nft at `now`, for a single NFT
+    pub fn unlocked_amount(&self, now: i64) -> u64 {
+        let cliff_end = self.vesting_start_ts.saturating_add(self.cliff_seconds);
+        if now < cliff_end {
+            return 0;
+        }
+
+        let elapsed = now - cliff_end;
+        if self.vesting_seconds == 0 || elapsed >= self.vesting_seconds {
+            return self.amount_per_nft;
+        }
+
+        (self.amount_per_nft as u128 * elapsed as u128 / self.vesting_seconds as u128) as u64
+    }
+
+    // True when `args` unlocks at least as much as the current schedule at
+    // any time: no lower amount, no later cliff end, no longer vesting
+    pub fn is_unlocked_no_later_by(&self, args: &SetTierAllocationArgs) -> bool {
+        let cliff_end = self.vesting_start_ts.saturating_add(self.cliff_seconds);
+        let new_cliff_end = args.vesting_start_ts.saturating_add(args.cliff_seconds);
+
+        args.amount_per_nft >= self.amount_per_nft
+            && new_cliff_end <= cliff_end
+            && args.vesting_seconds <= self.vesting_seconds
+    }
+}
+
+// One per NFT mint: tracks how much of the NFT's allocation was paid out
+#[account]
+#[derive(InitSpace)]
+pub struct ClaimRecord {
+    pub nft_mint: Pubkey,        // part of the PDA seeds
+    pub tier_id: u8,
+    pub bump: u8,
+    pub claimant: Pubkey,        // holder at the last claim
+    pub claimed_amount: u64,     // cumulative, across all holders of the NFT
+    pub claimed_at: i64,         // last claim
+}
+
+// ---------------------------------------------
+// Per-NFT record (tier + serial, written by every mint path)
+// ---------------------------------------------
+pub const NFT_RECORD_SEED: &[u8] = b"vigri-presale-nft";
+
+pub const NFT_RECORD_SPACE: usize = 8 + NftRecord::INIT_SPACE;
+
+// One per presale NFT mint, never closed (burned NFTs keep their record)
+#[account]
+#[derive(InitSpace)]
+pub struct NftRecord {
+    pub mint: Pubkey,            // part of the PDA seeds
+    pub tier_id: u8,
+    pub serial: u16,
+    pub bump: u8,
+    pub paid_lamports: u64,      // paid on-chain for this NFT (0 = grant or voucher), caps its buyback
+}
+
+// ---------------------------------------------
+// Staking (NFTs stay in the wallet, frozen via a PDA delegate)
+// ---------------------------------------------
+pub const STAKE_AUTHORITY_SEED: &[u8] = b"vigri-presale-stake-authority";
+pub const STAKE_SEED: &[u8] = b"vigri-presale-stake";
+pub const STAKER_POINTS_SEED: &[u8] = b"vigri-presale-points";
+
+pub const STAKE_RECORD_SPACE: usize = 8 + StakeRecord::INIT_SPACE;
+pub const STAKER_POINTS_SPACE: usize = 8 + StakerPoints::INIT_SPACE;
+
+// One per staked NFT mint, closed on unstake
+#[account]
+#[derive(InitSpace)]
+pub struct StakeRecord {
+    pub nft_mint: Pubkey,        // part of the PDA seeds
+    pub owner: Pubkey,           // wallet that staked (only it can unstake)
+    pub tier_id: u8,
+    pub bump: u8,
+    pub staked_at: i64,
+    pub stake_weight: u16,       // tier.stake_weight at stake time
+}
+
+// One per wallet, accumulates points across all its stakes
+#[account]
+#[derive(InitSpace)]
+pub struct StakerPoints {
+    pub owner: Pubkey,           // part of the PDA seeds
+    pub bump: u8,
+    pub points: u64,             // booked on unstake: stake_record.stake_weight x seconds
+    pub staked_count: u16,       // NFTs currently staked
+    pub reserved: [u8; 32],      // future use, keep zeroed
+}
+
+// ---------------------------------------------
+// Physical redemption (NFT locked via a PDA delegate until burned)
+// ---------------------------------------------
+pub const REDEMPTION_AUTHORITY_SEED: &[u8] = b"vigri-presale-redemption-authority";
+pub const REDEMPTION_SEED: &[u8] = b"vigri-presale-redemption";
+
+pub const REDEMPTION_SPACE: usize = 8 + Redemption::INIT_SPACE;
+
+// Shipped redemptions the holder doesn't confirm can be completed by the admin after this
+pub const REDEMPTION_CONFIRM_TIMEOUT_SECONDS: i64 = 30 * 24 * 60 * 60; // 30 days
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum RedemptionStatus {
+    Requested,
+    Approved,
+    Shipped,
+    Completed,
+    Rejected,
+}
+
+// One per NFT mint under redemption (kept after completion)
+#[account]
+#[derive(InitSpace)]
+pub struct Redemption {
+    pub nft_mint: Pubkey,        // part of the PDA seeds
+    pub owner: Pubkey,           // holder that requested
+    pub tier_id: u8,
+    pub bump: u8,
+    pub status: RedemptionStatus,
+    pub shipping_info_hash: [u8; 32], // hash of the encrypted shipping info (kept off-chain)
+    pub requested_at: i64,
+    pub updated_at: i64,
+}
+
+// Accounts to thaw a locked NFT and drop the redemption delegate
+struct RedemptionLockAccounts<'info> {
+    owner: AccountInfo<'info>,
+    redemption_authority: AccountInfo<'info>,
+    nft_mint: AccountInfo<'info>,
+    nft_token_account: AccountInfo<'info>,
+    nft_metadata: AccountInfo<'info>,
+    nft_edition: AccountInfo<'info>,
+    token_metadata_program: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+}
+
+fn unlock_redeemed_nft(accounts: &RedemptionLockAccounts<'_>, authority_bump: u8) -> Result<()> {
+    let signer_seeds: &[&[u8]] = &[REDEMPTION_AUTHORITY_SEED, &[authority_bump]];
+    let signer: &[&[&[u8]]] = &[signer_seeds];
+
+    let cpi_ctx_thaw = CpiContext::new_with_signer(
+        accounts.token_metadata_program.clone(),
+        ThawDelegatedAccount {
+            metadata: accounts.nft_metadata.clone(),
+            delegate: accounts.redemption_authority.clone(),
+            token_account: accounts.nft_token_account.clone(),
+            edition: accounts.nft_edition.clone(),
+            mint: accounts.nft_mint.clone(),
+            token_program: accounts.token_program.clone(),
+        },
+        signer,
+    );
+    thaw_delegated_account(cpi_ctx_thaw)?;
+
+    let cpi_ctx_revoke = CpiContext::new(
+        accounts.token_program.clone(),
+        token::Revoke {
+            source: accounts.nft_token_account.clone(),
+            authority: accounts.owner.clone(),
+        },
+    );
+    token::revoke(cpi_ctx_revoke)
+}
+
+// ---------------------------------------------
+// Certificates of authenticity (one PDA per tier + serial)
+// ---------------------------------------------
+pub const CERTIFICATE_SEED: &[u8] = b"vigri-presale-certificate";
+
+pub const CERTIFICATE_SPACE: usize = 8 + Certificate::INIT_SPACE;
+
+// Binds a serial (and its mint) to the physical piece's certificate / assay data
+#[account]
+#[derive(InitSpace)]
+pub struct Certificate {
+    pub tier_id: u8,             // part of the PDA seeds
+    pub serial: u16,             // part of the PDA seeds (le bytes)
+    pub bump: u8,
+    pub mint: Pubkey,            // NFT mint carrying this serial
+    pub certificate_hash: [u8; 32], // hash of the certificate / assay document
+    pub issued_at: i64,
+    pub updated_at: i64,
+}
+
+// ---------------------------------------------
+// Buyback (treasury pays a per-tier floor price for returned NFTs)
+// ---------------------------------------------
+pub const BUYBACK_SEED: &[u8] = b"vigri-presale-buyback";
+// Owner of the ATAs holding bought-back NFTs kept for re-sale
+pub const BUYBACK_VAULT_SEED: &[u8] = b"vigri-presale-buyback-vault";
+
+pub const BUYBACK_CONFIG_SPACE: usize = 8 + BuybackConfig::INIT_SPACE;
+
+#[account]
+#[derive(InitSpace)]
+pub struct BuybackConfig {
+    pub bump: u8,
+    pub is_paused: bool,         // buyback pause switch
+    pub budget_lamports: u64,    // total the treasury may spend on buybacks
+    pub spent_lamports: u64,     // paid out so far, never above budget_lamports
+    pub burn_returned: bool,     // true = burn, false = hold in the buyback vault
+    pub reserved: [u8; 32],      // future use, keep zeroed
+}
+
+// Moves a held NFT out of the buyback vault and closes the vault ATA
+// (its rent goes to the treasury, which paid for the NFT)
+fn release_from_buyback_vault<'info>(
+    vault_token_account: &Account<'info, TokenAccount>,
+    destination_token_account: &Account<'info, TokenAccount>,
+    buyback_vault: &AccountInfo<'info>,
+    treasury: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    vault_bump: u8,
+) -> Result<()> {
+    require!(
+        vault_token_account.owner == buyback_vault.key()
+            && vault_token_account.amount == 1
+            && destination_token_account.mint == vault_token_account.mint,
+        PresaleError::InvalidPresaleNft
+    );
+
+    let signer_seeds: &[&[u8]] = &[BUYBACK_VAULT_SEED, &[vault_bump]];
+    let signer: &[&[&[u8]]] = &[signer_seeds];
+
+    let cpi_ctx_transfer = CpiContext::new_with_signer(
+        token_program.clone(),
+        token::Transfer {
+            from: vault_token_account.to_account_info(),
+            to: destination_token_account.to_account_info(),
+            authority: buyback_vault.clone(),
+        },
+        signer,
+    );
+    token::transfer(cpi_ctx_transfer, 1)?;
+
+    let cpi_ctx_close = CpiContext::new_with_signer(
+        token_program.clone(),
+        token::CloseAccount {
+            account: vault_token_account.to_account_info(),
+            destination: treasury.clone(),
+            authority: buyback_vault.clone(),
+        },
+        signer,
+    );
+    token::close_account(cpi_ctx_close)
+}
+
+// ---------------------------------------------
+// Purchase vouchers (off-chain payments, signed by the backend)
+// ---------------------------------------------
+pub const VOUCHER_SEED: &[u8] = b"vigri-presale-voucher";
+// Prefix of the signed message, so no other signed payload can pass as a voucher
+pub const VOUCHER_MESSAGE_DOMAIN: &[u8] = b"vigri-presale-voucher-v1";
+
+pub const VOUCHER_RECORD_SPACE: usize = 8 + VoucherRecord::INIT_SPACE;
+
+// What the backend signs: voucher_message() = domain + program id + borsh(voucher)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct Voucher {
+    pub tier_id: u8,
+    pub recipient: Pubkey,
+    // Only used for tier_id == 0 (Tree/Steel): 1 = TR (Tree), 2 = FE (Steel)
+    pub design_choice: Option<u8>,
+    pub expires_at: i64, // unix timestamp, inclusive
+    pub nonce: u64,      // unique per voucher, part of the VoucherRecord seeds
+}
+
+// Used-nonce marker: its existence makes a voucher unredeemable
+#[account]
+#[derive(InitSpace)]
+pub struct VoucherRecord {
+    pub nonce: u64,       // part of the PDA seeds (le bytes)
+    pub bump: u8,
+    pub tier_id: u8,
+    pub recipient: Pubkey,
+    pub mint: Pubkey,     // NFT minted for this voucher
+    pub redeemed_at: i64,
+}
+
+// ---------------------------------------------
+// KYC registry (one PDA per verified wallet, written by the KYC manager)
+// ---------------------------------------------
+pub const KYC_SEED: &[u8] = b"vigri-presale-kyc";
+
+pub const KYC_RECORD_SPACE: usize = 8 + KycRecord::INIT_SPACE;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum KycLevel {
+    Basic,
+    Enhanced,
+}
+
+// Any unexpired entry passes kyc_required; level and country are for off-chain policy
+#[account]
+#[derive(InitSpace)]
+pub struct KycRecord {
+    pub wallet: Pubkey,          // part of the PDA seeds
+    pub bump: u8,
+    pub level: KycLevel,
+    pub expires_at: i64,         // unix timestamp, entry is invalid from then on
+    pub country_code: [u8; 2],   // ISO 3166-1 alpha-2, e.g. b"EE"
+    pub verified_at: i64,        // first write
+    pub updated_at: i64,
+}
+
+// ---------------------------------------------
+// Operator roles
+// ---------------------------------------------
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Pauser,             // global pause switch
+    PriceManager,       // tier prices
+    Minter,             // admin_mint
+    KycManager,         // KYC gating of tiers + KYC registry
+    TreasuryWithdrawer, // withdraw_treasury
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct RoleKeys {
+    pub pauser: Pubkey,
+    pub price_manager: Pubkey,
+    pub minter: Pubkey,
+    pub kyc_manager: Pubkey,
+    pub treasury_withdrawer: Pubkey,
+}
+
+impl RoleKeys {
+    pub fn key(&self, role: Role) -> Pubkey {
+        match role {
+            Role::Pauser => self.pauser,
+            Role::PriceManager => self.price_manager,
+            Role::Minter => self.minter,
+            Role::KycManager => self.kyc_manager,
+            Role::TreasuryWithdrawer => self.treasury_withdrawer,
+        }
+    }
+
+    pub fn key_mut(&mut self, role: Role) -> &mut Pubkey {
+        match role {
+            Role::Pauser => &mut self.pauser,
+            Role::PriceManager => &mut self.price_manager,
+            Role::Minter => &mut self.minter,
+            Role::KycManager => &mut self.kyc_manager,
+            Role::TreasuryWithdrawer => &mut self.treasury_withdrawer,
+        }
+    }
+}
+
+// Resize a program-owned account, keeping it exactly rent-exempt:
+// `funder` pays when growing and receives the excess when shrinking.
+fn resize_program_account<'info>(
+    account: &AccountInfo<'info>,
+    funder: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    new_len: usize,
+) -> Result<()> {
+    let required = Rent::get()?.minimum_balance(new_len);
+    let current = account.lamports();
+
+    if required > current {
+        let cpi_ctx = CpiContext::new(
+            system_program.to_account_info(),
+            system_program::Transfer {
+                from: funder.clone(),
+                to: account.clone(),
+            },
+        );
+        system_program::transfer(cpi_ctx, required - current)?;
+    } else if current > required {
+        **account.try_borrow_mut_lamports()? -= current - required;
+        **funder.try_borrow_mut_lamports()? += current - required;
+    }
+
+    account.realloc(new_len, true)?;
+
+    Ok(())
+}
+
+// Creates a program-owned PDA, also when someone pre-funded its address
+fn create_program_pda<'info>(
+    account: &AccountInfo<'info>,
+    funder: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    space: usize,
+    signer_seeds: &[&[u8]],
+) -> Result<()> {
+    let required = Rent::get()?.minimum_balance(space);
+    let current = account.lamports();
+    let signer = &[signer_seeds];
+
+    if current == 0 {
+        let cpi_ctx = CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            system_program::CreateAccount {
+                from: funder.clone(),
+                to: account.clone(),
+            },
+            signer,
+        );
+        return system_program::create_account(cpi_ctx, required, space as u64, &crate::ID);
+    }
+
+    if required > current {
+        let cpi_ctx = CpiContext::new(
+            system_program.to_account_info(),
+            system_program::Transfer {
+                from: funder.clone(),
+                to: account.clone(),
+            },
+        );
+        system_program::transfer(cpi_ctx, required - current)?;
+    }
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        system_program.to_account_info(),
+        system_program::Allocate {
+            account_to_allocate: account.clone(),
+        },
+        signer,
+    );
+    system_program::allocate(cpi_ctx, space as u64)?;
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        system_program.to_account_info(),
+        system_program::Assign {
+            account_to_assign: account.clone(),
+        },
+        signer,
+    );
+    system_program::assign(cpi_ctx, &crate::ID)
+}
+
+fn build_uri(tier: &TierConfig, serial: u16, design_choice: Option<u8>) -> Result<String> {
+    let serial6 = format!("{:06}", serial);
+
+    let uri = if tier.id == TierId::TreeSteel as u8 {
+        // Tree/Steel (choice): <base>/TR|FE/<serial>.json
+        let code = match design_choice {
+            Some(1) => "TR",
+            Some(2) => "FE",
+            _ => return err!(PresaleError::InvalidDesignChoice),
+        };
+        format!("{}/{}/{}.json", tier.uri_base, code, serial6)
+    } else {
+        // Metal tiers: <base>/<serial>.json, e.g. .../bronze/CU/000001.json
+        format!("{}/{}.json", tier.uri_base, serial6)
+    };
+
+    Ok(uri)
+}
+
+fn build_name(tier: &TierConfig, serial: u16, design_choice: Option<u8>) -> Result<String> {
+    // For Tree/Steel we specify by design_choice
+    let label = if tier.id == TierId::TreeSteel as u8 {
+        match design_choice {
+            Some(1) => "Tree",
+            Some(2) => "Steel",
+            _ => tier.label.as_str(), // fallback
+        }
+    } else {
+        tier.label.as_str()
     };
 
-    // For Tree/Steel we specify by design_choice
-    let label = if tier_id == 0 {
-        match design_choice {
-            Some(1) => "Tree",
-            Some(2) => "Steel",
-            _ => base_label, // fallback
-        }
-    } else {
-        base_label
-    };
+    // Metaplex name limit is strict; keep it short and deterministic
+    let serial6 = format!("{:06}", serial);
+    let name = format!("VIGRI {} NFT #{}", label, serial6);
+
+    Ok(name)
+}
+
+#[event]
+pub struct NftMinted {
+    pub tier_id: u8,
+    pub serial: u16,
+    pub design_key: u16,
+    pub mint: Pubkey,
+    pub recipient: Pubkey, // owner of the token account the NFT was minted into
+    pub buyer: Pubkey,     // signer that paid (payer for public mints, minter for grants)
+    pub price_paid: u64,   // 0 for admin grants and vouchers
+    pub payment_mint: Pubkey, // currency of price_paid (NATIVE_SOL_MINT = lamports)
+    pub mint_path: MintPath,
+    pub uri: String,
+    pub slot: u64,
+    pub unix_timestamp: i64,
+}
+
+// Which instruction produced an NftMinted event
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MintPath {
+    Public,  // mint_nft
+    Admin,   // admin_mint
+    Airdrop, // admin_airdrop
+    Upgrade, // upgrade_nft
+    Voucher, // redeem_voucher (paid off-chain)
+}
+
+// Wrapped SOL mint, used as the currency of lamport payments in events
+pub const NATIVE_SOL_MINT: Pubkey = anchor_spl::token::spl_token::native_mint::ID;
+
+#[event]
+pub struct NftUpgraded {
+    pub owner: Pubkey,
+    pub old_mint: Pubkey,
+    pub from_tier_id: u8,
+    pub new_mint: Pubkey,
+    pub to_tier_id: u8,
+    pub serial: u16, // new serial inside to_tier
+    pub price_paid: u64,
+}
+
+#[event]
+pub struct ConfigInitialized {
+    pub admin: Pubkey,
+    pub collection_mint: Pubkey,
+    pub payment_mint: Pubkey,
+    pub version: u8,
+    pub config_delay_seconds: i64,
+}
+
+#[event]
+pub struct ConfigMigrated {
+    pub from_version: u8,
+    pub to_version: u8,
+}
+
+#[event]
+pub struct TierMigrated {
+    pub tier_id: u8,
+    pub from_version: u8,
+    pub to_version: u8,
+    pub admin_quota: u16,
+    pub supply_max: u16,
+}
+
+#[event]
+pub struct NftRecordRegistered {
+    pub mint: Pubkey,
+    pub tier_id: u8,
+    pub serial: u16,
+    pub paid_lamports: u64,
+}
+
+#[event]
+pub struct SalesPaused {
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct SalesResumed {
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct TierSalesPaused {
+    pub tier_id: u8,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct TierSalesResumed {
+    pub tier_id: u8,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct TierCreated {
+    pub tier_id: u8,
+    pub supply_total: u16,
+    pub supply_max: u16,
+    pub price_lamports: u64,
+    pub admin_quota: u16,
+    pub stake_weight: u16,
+    pub kyc_required: bool,
+    pub invite_only: bool,
+    pub transferable: bool,
+}
+
+#[event]
+pub struct TierClosed {
+    pub tier_id: u8,
+}
+
+#[event]
+pub struct TierPriceChanged {
+    pub tier_id: u8,
+    pub old_price_lamports: u64,
+    pub new_price_lamports: u64,
+}
+
+#[event]
+pub struct TierSupplyChanged {
+    pub tier_id: u8,
+    pub old_supply_total: u16,
+    pub new_supply_total: u16,
+}
+
+#[event]
+pub struct TierFlagsChanged {
+    pub tier_id: u8,
+    pub kyc_required: bool,
+    pub invite_only: bool,
+    pub transferable: bool,
+    pub supply_locked: bool,
+    pub anti_bot_max_mints: u8,
+}
+
+#[event]
+pub struct AdminTransferProposed {
+    pub admin: Pubkey,
+    pub proposed_admin: Pubkey,
+}
+
+#[event]
+pub struct AdminTransferAccepted {
+    pub previous_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
+
+#[event]
+pub struct AdminTransferCancelled {
+    pub admin: Pubkey,
+    pub cancelled_admin: Pubkey,
+}
+
+#[event]
+pub struct RoleUpdated {
+    pub role: Role,
+    pub previous: Pubkey,
+    pub key: Pubkey,
+}
+
+#[event]
+pub struct TreasuryWithdrawn {
+    pub authority: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct TierChangeQueued {
+    pub tier_id: u8,
+    pub change: TierChange,
+}
+
+#[event]
+pub struct TierChangeApplied {
+    pub tier_id: u8,
+    pub change: TierChange,
+}
+
+#[event]
+pub struct CollectionMintChangeQueued {
+    pub collection_mint: Pubkey,
+    pub effective_at: i64,
+}
+
+#[event]
+pub struct CollectionMintChanged {
+    pub old_collection_mint: Pubkey,
+    pub new_collection_mint: Pubkey,
+}
+
+#[event]
+pub struct ConfigDelayUpdated {
+    pub delay_seconds: i64,
+}
+
+#[event]
+pub struct ConfigDelayChangeQueued {
+    pub delay_seconds: i64,
+    pub effective_at: i64,
+}
+
+#[event]
+pub struct RateLimitUpdated {
+    pub scope: u8,
+    pub max_mints: u16,
+    pub window_slots: u64,
+}
+
+#[event]
+pub struct TierAllocationUpdated {
+    pub tier_id: u8,
+    pub token_mint: Pubkey,
+    pub amount_per_nft: u64,
+    pub vesting_start_ts: i64,
+    pub cliff_seconds: i64,
+    pub vesting_seconds: i64,
+}
+
+#[event]
+pub struct AllocationClaimed {
+    pub tier_id: u8,
+    pub nft_mint: Pubkey,
+    pub claimant: Pubkey,
+    pub amount: u64,
+    pub claimed_total: u64, // for this NFT, across all claims
+}
+
+#[event]
+pub struct NftStaked {
+    pub owner: Pubkey,
+    pub nft_mint: Pubkey,
+    pub tier_id: u8,
+    pub staked_at: i64,
+    pub stake_weight: u16,
+}
+
+#[event]
+pub struct NftUnstaked {
+    pub owner: Pubkey,
+    pub nft_mint: Pubkey,
+    pub tier_id: u8,
+    pub staked_seconds: u64,
+    pub points_earned: u64,
+    pub points_total: u64,
+}
+
+#[event]
+pub struct RedemptionRequested {
+    pub nft_mint: Pubkey,
+    pub owner: Pubkey,
+    pub tier_id: u8,
+    pub shipping_info_hash: [u8; 32],
+}
+
+#[event]
+pub struct RedemptionStatusChanged {
+    pub nft_mint: Pubkey,
+    pub old_status: RedemptionStatus,
+    pub new_status: RedemptionStatus,
+}
+
+#[event]
+pub struct RedemptionCancelled {
+    pub nft_mint: Pubkey,
+    pub owner: Pubkey,
+}
+
+#[event]
+pub struct RedemptionCompleted {
+    pub nft_mint: Pubkey,
+    pub owner: Pubkey,
+    pub tier_id: u8,
+}
+
+#[event]
+pub struct CertificateRecorded {
+    pub tier_id: u8,
+    pub serial: u16,
+    pub mint: Pubkey,
+    pub certificate_hash: [u8; 32],
+}
+
+#[event]
+pub struct BuybackConfigUpdated {
+    pub is_paused: bool,
+    pub budget_lamports: u64,
+    pub spent_lamports: u64,
+    pub burn_returned: bool,
+}
+
+#[event]
+pub struct BuybackPriceChanged {
+    pub tier_id: u8,
+    pub old_price_lamports: u64,
+    pub new_price_lamports: u64,
+}
+
+#[event]
+pub struct BuybackNftReleased {
+    pub nft_mint: Pubkey,
+    pub destination: Pubkey,
+}
+
+#[event]
+pub struct NftBoughtBack {
+    pub nft_mint: Pubkey,
+    pub seller: Pubkey,
+    pub tier_id: u8,
+    pub price_lamports: u64,
+    pub burned: bool,
+}
+
+#[event]
+pub struct VoucherSignerUpdated {
+    pub old_signer: Pubkey,
+    pub new_signer: Pubkey,
+}
+
+#[event]
+pub struct VoucherSignerChangeQueued {
+    pub voucher_signer: Pubkey,
+    pub effective_at: i64,
+}
+
+#[event]
+pub struct VoucherRedeemed {
+    pub nonce: u64,
+    pub tier_id: u8,
+    pub serial: u16,
+    pub mint: Pubkey,
+    pub recipient: Pubkey,
+}
+
+#[event]
+pub struct KycRecordUpdated {
+    pub wallet: Pubkey,
+    pub level: KycLevel,
+    pub expires_at: i64,
+    pub country_code: [u8; 2],
+    pub operator: Pubkey,
+}
+
+#[event]
+pub struct KycRecordRevoked {
+    pub wallet: Pubkey,
+    pub operator: Pubkey,
+}
+
+#[event]
+pub struct MultisigUpdated {
+    pub signers: Vec<Pubkey>,
+    pub threshold: u8,
+    pub signers_epoch: u32,
+}
+
+#[event]
+pub struct ActionProposed {
+    pub proposal_id: u64,
+    pub proposer: Pubkey,
+    pub action: ProposedAction,
+}
+
+#[event]
+pub struct ActionApproved {
+    pub proposal_id: u64,
+    pub signer: Pubkey,
+    pub approvals: u8,
+}
+
+#[event]
+pub struct ActionExecuted {
+    pub proposal_id: u64,
+    pub executor: Pubkey,
+}
+
+fn resolve_design_key(tier: &TierConfig, serial: u16, design_choice: Option<u8>) -> Result<u16> {
+    if tier.id == TierId::TreeSteel as u8 {
+        return match design_choice {
+            Some(1) => Ok(1), // TR
+            Some(2) => Ok(2), // FE
+            _ => err!(PresaleError::InvalidDesignChoice),
+        };
+    }
+
+    match tier.design_variants {
+        0 => Ok(serial),                 // AU / PT / WS: unique per serial
+        n => Ok(((serial - 1) % n) + 1), // CU: 1, AG: 1..10
+    }
+}
+
+// Supply + admin quota checks shared by admin_mint and admin_airdrop
+fn check_admin_mint_allowed(tier: &TierConfig) -> Result<()> {
+    require!(tier.supply_minted < tier.supply_total, PresaleError::TierSoldOut);
+    require!(
+        tier.admin_minted < tier.admin_quota,
+        PresaleError::AdminQuotaExceeded
+    );
+
+    Ok(())
+}
+
+// kyc_required tiers: the wallet receiving the NFT needs an unexpired registry entry
+fn check_kyc(record: Option<&KycRecord>, now: i64) -> Result<()> {
+    let record = record.ok_or(error!(PresaleError::KycRequired))?;
+    require!(now < record.expires_at, PresaleError::KycExpired);
+
+    Ok(())
+}
+
+// Compute budget program (not re-exported by solana_program 2.x)
+pub const COMPUTE_BUDGET_PROGRAM_ID: Pubkey =
+    pubkey!("ComputeBudget111111111111111111111111111111");
+
+// Anti-bot mode: every instruction in the transaction must be a top-level
+// mint_nft / upgrade_nft or a whitelisted companion, and those may appear at
+// most `max_mints` times. A CPI caller shows up as a foreign top-level instruction.
+fn check_anti_bot(instructions: &AccountInfo, max_mints: u8) -> Result<()> {
+    let current_index = load_current_index_checked(instructions)? as usize;
+    let current = load_instruction_at_checked(current_index, instructions)?;
+    require_keys_eq!(current.program_id, crate::ID, PresaleError::AntiBotRejected);
+
+    let mut mints: u8 = 0;
+    let mut index = 0;
+    // Instruction count is not exposed directly; loading past the end errors
+    while let Ok(ix) = load_instruction_at_checked(index, instructions) {
+        if ix.program_id == crate::ID {
+            require!(
+                ix.data.starts_with(instruction::MintNft::DISCRIMINATOR)
+                    || ix.data.starts_with(instruction::UpgradeNft::DISCRIMINATOR),
+                PresaleError::AntiBotRejected
+            );
+            mints = mints.saturating_add(1);
+        } else {
+            require!(
+                ix.program_id == COMPUTE_BUDGET_PROGRAM_ID
+                    || ix.program_id == ed25519_program::ID
+                    || ix.program_id == associated_token::ID,
+                PresaleError::AntiBotRejected
+            );
+        }
+        index += 1;
+    }
+
+    require!(mints <= max_mints, PresaleError::AntiBotRejected);
+
+    Ok(())
+}
+
+// Bytes the backend signs for a voucher (bound to this program)
+pub fn voucher_message(voucher: &Voucher) -> Result<Vec<u8>> {
+    let mut message = Vec::with_capacity(VOUCHER_MESSAGE_DOMAIN.len() + 32 + 64);
+    message.extend_from_slice(VOUCHER_MESSAGE_DOMAIN);
+    message.extend_from_slice(crate::ID.as_ref());
+    voucher.serialize(&mut message)?;
+
+    Ok(message)
+}
+
+// ed25519 program instruction layout: [count u8, padding u8] followed by
+// `count` offset records of 7 little-endian u16 each
+const ED25519_OFFSETS_START: usize = 2;
+const ED25519_OFFSETS_SIZE: usize = 14;
+
+// The ed25519 program verifies the signature when the transaction runs; here
+// we only check that the instruction right before ours verified exactly one
+// signature, by `signer`, over `message`, with all data inline.
+fn check_voucher_signature(
+    instructions: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+) -> Result<()> {
+    let current_index = load_current_index_checked(instructions)? as usize;
+    require!(current_index > 0, PresaleError::InvalidVoucher);
+    let ix = load_instruction_at_checked(current_index - 1, instructions)?;
+    require_keys_eq!(ix.program_id, ed25519_program::ID, PresaleError::InvalidVoucher);
+
+    let data = &ix.data;
+    require!(
+        data.len() >= ED25519_OFFSETS_START + ED25519_OFFSETS_SIZE && data[0] == 1,
+        PresaleError::InvalidVoucher
+    );
+    let offsets: Vec<usize> = data
+        [ED25519_OFFSETS_START..ED25519_OFFSETS_START + ED25519_OFFSETS_SIZE]
+        .chunks_exact(2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]) as usize)
+        .collect();
+    // signature, public key and message must live in the ed25519 instruction itself
+    let inline = u16::MAX as usize;
+    require!(
+        offsets[1] == inline && offsets[3] == inline && offsets[6] == inline,
+        PresaleError::InvalidVoucher
+    );
+
+    let (pubkey_offset, message_offset, message_size) = (offsets[2], offsets[4], offsets[5]);
+    let pubkey = data
+        .get(pubkey_offset..pubkey_offset + 32)
+        .ok_or(error!(PresaleError::InvalidVoucher))?;
+    let signed = data
+        .get(message_offset..message_offset + message_size)
+        .ok_or(error!(PresaleError::InvalidVoucher))?;
+    require!(
+        pubkey == signer.as_ref() && signed == message,
+        PresaleError::InvalidVoucher
+    );
+
+    Ok(())
+}
+
+// A presale NFT is ours if its metadata is updated by the GlobalConfig PDA and
+// sits in our verified collection; its tier comes from the NftRecord written
+// at mint time (uri prefixes are ambiguous once one uri_base nests another).
+fn verify_presale_nft(
+    metadata: &MetadataAccount,
+    mint: Pubkey,
+    global_config: &GlobalConfig,
+    global_config_key: Pubkey,
+    record: &NftRecord,
+    tier: &TierConfig,
+) -> Result<()> {
+    verify_presale_metadata(metadata, mint, global_config, global_config_key)?;
+
+    require!(
+        record.mint == mint && record.tier_id == tier.id,
+        PresaleError::InvalidPresaleNft
+    );
+
+    Ok(())
+}
+
+fn verify_presale_metadata(
+    metadata: &MetadataAccount,
+    mint: Pubkey,
+    global_config: &GlobalConfig,
+    global_config_key: Pubkey,
+) -> Result<()> {
+    require_keys_eq!(metadata.mint, mint, PresaleError::InvalidPresaleNft);
+    require_keys_eq!(
+        metadata.update_authority,
+        global_config_key,
+        PresaleError::InvalidPresaleNft
+    );
+
+    let in_collection = metadata
+        .collection
+        .as_ref()
+        .is_some_and(|c| c.verified && c.key == global_config.collection_mint);
+    require!(in_collection, PresaleError::InvalidPresaleNft);
+
+    Ok(())
+}
+
+// DataV2 for a presale NFT: collection is set via set_and_verify_collection
+fn presale_nft_data(
+    tier: &TierConfig,
+    serial: u16,
+    design_choice: Option<u8>,
+    creator: Pubkey,
+) -> Result<DataV2> {
+    Ok(DataV2 {
+        // On-chain name shown by wallets (must be short enough for Metaplex constraints)
+        name: build_name(tier, serial, design_choice)?,
+        symbol: PLACEHOLDER_SYMBOL.to_string(),
+        uri: build_uri(tier, serial, design_choice)?,
+        seller_fee_basis_points: 250,
+        creators: Some(vec![Creator {
+            address: creator,
+            verified: false,
+            share: 100,
+        }]),
+        collection: None,
+        uses: None,
+    })
+}
+
+// Accounts needed to turn a freshly minted token into a presale NFT.
+// `payer` pays rent and is also the (temporary) mint authority.
+struct NftMetadataAccounts<'info> {
+    payer: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    metadata: AccountInfo<'info>,
+    edition: AccountInfo<'info>,
+    global_config: AccountInfo<'info>,
+    collection_mint: AccountInfo<'info>,
+    collection_metadata: AccountInfo<'info>,
+    collection_master_edition: AccountInfo<'info>,
+    token_metadata_program: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    rent: AccountInfo<'info>,
+}
+
+// Metadata + master edition (max supply 0) + verified collection.
+// GlobalConfig PDA is update authority and collection authority.
+fn create_presale_nft_metadata(
+    accounts: &NftMetadataAccounts<'_>,
+    data: DataV2,
+    global_config_bump: u8,
+    collection_mint_key: Pubkey,
+) -> Result<()> {
+    let signer_seeds: &[&[u8]] = &[GLOBAL_CONFIG_SEED, &[global_config_bump]];
+    let signer: &[&[&[u8]]] = &[signer_seeds];
+
+    // a) Metadata
+    let cpi_ctx = CpiContext::new_with_signer(
+        accounts.token_metadata_program.clone(),
+        CreateMetadataAccountsV3 {
+            metadata: accounts.metadata.clone(),
+            mint: accounts.mint.clone(),
+            mint_authority: accounts.payer.clone(),
+            update_authority: accounts.global_config.clone(),
+            payer: accounts.payer.clone(),
+            system_program: accounts.system_program.clone(),
+            rent: accounts.rent.clone(),
+        },
+        signer,
+    );
+
+    create_metadata_accounts_v3(
+        cpi_ctx,
+        data,
+        true, // is_mutable
+        true, // update_authority_is_signer (PDA signs via seeds)
+        None, // collection_details
+    )?;
+
+    // b) Master edition
+    let cpi_ctx_edition = CpiContext::new_with_signer(
+        accounts.token_metadata_program.clone(),
+        CreateMasterEditionV3 {
+            edition: accounts.edition.clone(),
+            mint: accounts.mint.clone(),
+            update_authority: accounts.global_config.clone(),
+            mint_authority: accounts.payer.clone(),
+            payer: accounts.payer.clone(),
+            metadata: accounts.metadata.clone(),
+            token_program: accounts.token_program.clone(),
+            system_program: accounts.system_program.clone(),
+            rent: accounts.rent.clone(),
+        },
+        signer,
+    );
+
+    create_master_edition_v3(cpi_ctx_edition, Some(0))?;
+
+    // c) Link to the collection and verify (if collection_mint is configured)
+    if collection_mint_key != Pubkey::default() {
+        let cpi_ctx_collection = CpiContext::new_with_signer(
+            accounts.token_metadata_program.clone(),
+            SetAndVerifyCollection {
+                metadata: accounts.metadata.clone(),
+                collection_authority: accounts.global_config.clone(),
+                payer: accounts.payer.clone(),
+                update_authority: accounts.global_config.clone(),
+                collection_mint: accounts.collection_mint.clone(),
+                collection_metadata: accounts.collection_metadata.clone(),
+                collection_master_edition: accounts.collection_master_edition.clone(),
+            },
+            signer,
+        );
+
+        // collection_authority_record = None (there is no separate recording PDA)
+        set_and_verify_collection(cpi_ctx_collection, None)?;
+    }
+
+    Ok(())
+}
+
+// ---------------------------------------------
+// Airdrop batching (client-side helper)
+// ---------------------------------------------
+// remaining_accounts per admin_airdrop recipient
+pub const AIRDROP_ACCOUNTS_PER_RECIPIENT: usize = 6;
+
+// Solana limits a batch has to fit into
+pub const MAX_TRANSACTION_SIZE: usize = 1232;
+pub const MAX_COMPUTE_UNITS: u32 = 1_400_000;
+
+// Static accounts of an admin_airdrop transaction: the 12 AdminAirdrop
+// accounts, event_authority + program added by #[event_cpi], and the
+// compute budget program
+pub const AIRDROP_STATIC_ACCOUNTS: usize = 12 + 2 + 1;
+// admin_airdrop instruction without per-recipient indexes: program index +
+// account count + static account indexes + data length + discriminator +
+// AdminAirdropArgs (Option<u8>)
+pub const AIRDROP_IX_BASE_SIZE: usize = 1 + 1 + (AIRDROP_STATIC_ACCOUNTS - 1) + 1 + 8 + 2;
+// SetComputeUnitLimit: program index + account count + data length + tag + u32
+pub const COMPUTE_BUDGET_IX_SIZE: usize = 1 + 1 + 1 + 1 + 4;
+
+// Size estimates for one admin_airdrop transaction:
+// base = signature/key/instruction counts + header + blockhash + authority
+// signature + static keys + both instructions,
+// per recipient = mint signature + 6 account keys + 6 account indexes.
+pub const AIRDROP_TX_BASE_SIZE: usize =
+    3 + 3 + 32 + 64 + 32 * AIRDROP_STATIC_ACCOUNTS + AIRDROP_IX_BASE_SIZE + COMPUTE_BUDGET_IX_SIZE;
+pub const AIRDROP_TX_SIZE_PER_RECIPIENT: usize = 64 + 32 * 6 + 6;
+// create mint + ATA + mint_to + metadata + master edition + verify collection
+// + NFT record
+pub const AIRDROP_COMPUTE_UNITS_PER_RECIPIENT: u32 = 270_000;
+
+// How many recipients fit into one admin_airdrop transaction
+pub fn airdrop_recipients_per_tx() -> usize {
+    let by_size = (MAX_TRANSACTION_SIZE - AIRDROP_TX_BASE_SIZE) / AIRDROP_TX_SIZE_PER_RECIPIENT;
+    let by_compute = (MAX_COMPUTE_UNITS / AIRDROP_COMPUTE_UNITS_PER_RECIPIENT) as usize;
+    by_size.min(by_compute).max(1)
+}
+
+// Split a recipient list into admin_airdrop batches (one transaction each)
+pub fn airdrop_batches(recipients: &[Pubkey]) -> std::slice::Chunks<'_, Pubkey> {
+    recipients.chunks(airdrop_recipients_per_tx())
+}
+
+// ---------------------------------------------
+// Instruction argument structs
+// ---------------------------------------------
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct InitializeArgs {
+    pub admin: Pubkey,
+    pub collection_mint: Pubkey,
+    pub payment_mint: Pubkey, // can be placeholder for native SOL in v1
+    pub config_delay_seconds: Option<i64>, // None = DEFAULT_CONFIG_DELAY_SECONDS
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct UpdateConfigArgs {
+    pub is_sales_paused: Option<bool>,
+    // Optional per-tier updates (applied to the passed tier account): price, flags, etc.
+    pub new_price_lamports: Option<u64>,
+    pub new_kyc_required: Option<bool>,
+    pub new_invite_only: Option<bool>,
+    pub new_transferable: Option<bool>,
+    pub new_admin_quota: Option<u16>,
+    pub new_supply_total: Option<u16>,
+    pub new_stake_weight: Option<u16>,
+    // One-way: once set, supply_total can only go down (applied immediately)
+    pub lock_supply: bool,
+    // Per-tier pause (pauser, applied immediately like is_sales_paused)
+    pub tier_paused: Option<bool>,
+    // Anti-bot mode: 0 = off, N = max mint_nft per transaction (admin, immediate)
+    pub new_anti_bot_max_mints: Option<u8>,
+    // When tier changes take effect (None = now + config delay, never earlier)
+    pub effective_at: Option<i64>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct MintNftArgs {
+    // Only used for tier_id == 0 (Tree/Steel):
+    // 1 = TR (Tree), 2 = FE (Steel)
+    pub design_choice: Option<u8>,
+
+    pub invite_proof: Option<Vec<u8>>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct UpgradeNftArgs {
+    // Only used when to_tier is Tree/Steel: 1 = TR (Tree), 2 = FE (Steel)
+    pub design_choice: Option<u8>,
+
+    pub invite_proof: Option<Vec<u8>>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SetTierAllocationArgs {
+    pub amount_per_nft: u64,
+    // Vesting schedule, e.g. Platinum: 6-month cliff then 18 months linear
+    pub vesting_start_ts: i64,
+    pub cliff_seconds: i64,
+    pub vesting_seconds: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SetBuybackConfigArgs {
+    pub is_paused: Option<bool>,       // pauser
+    pub budget_lamports: Option<u64>,  // admin, only while no multisig is active (else SetBuybackBudget)
+    pub burn_returned: Option<bool>,   // admin
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SetKycRecordArgs {
+    pub level: KycLevel,
+    pub expires_at: i64,       // must be in the future
+    pub country_code: [u8; 2], // uppercase ISO 3166-1 alpha-2
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct MintWs20Args {
+    // WS-20 mint requires a special invite proof
+    pub ws_invite_proof: Vec<u8>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct AdminMintArgs {
+    // Only used for tier_id == 0 (Tree/Steel):
+    // 1 = TR (Tree), 2 = FE (Steel)
+    pub design_choice: Option<u8>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CreateTierArgs {
+    pub tier_id: u8,
+    pub supply_total: u16,
+    pub price_lamports: u64,
+    pub kyc_required: bool,
+    pub invite_only: bool,
+    pub transferable: bool,
+    pub design_variants: u16,
+    pub label: String,
+    pub uri_base: String,
+    pub admin_quota: u16,
+    // Hard cap for later supply_total increases (>= supply_total, immutable)
+    pub supply_max: u16,
+    // Staking points per second staked (0 = staking earns nothing)
+    pub stake_weight: u16,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct AdminAirdropArgs {
+    // Only used for tier_id == 0 (Tree/Steel), applies to the whole batch:
+    // 1 = TR (Tree), 2 = FE (Steel)
+    pub design_choice: Option<u8>,
+}
+
+// ---------------------------------------------
+// Account context structs
+// ---------------------------------------------
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: authority to be stored as admin in GlobalConfig
+    pub admin: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = GLOBAL_CONFIG_SPACE,
+        seeds = [GLOBAL_CONFIG_SEED],
+        bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    /// Admin or a role key (checked per field in update_config)
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_CONFIG_SEED],
+        bump,
+        constraint = global_config.version == GLOBAL_CONFIG_VERSION @ PresaleError::ConfigNotMigrated,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Tier to update (only required for per-tier changes)
+    #[account(
+        mut,
+        seeds = [TIER_SEED, &[tier.id]],
+        bump = tier.bump,
+        constraint = tier.version == TIER_CONFIG_VERSION @ PresaleError::TierNotMigrated,
+    )]
+    pub tier: Option<Account<'info, TierConfig>>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateCollectionMint<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_CONFIG_SEED],
+        bump,
+        constraint = global_config.version == GLOBAL_CONFIG_VERSION @ PresaleError::ConfigNotMigrated,
+        has_one = admin,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    pub new_collection_mint: Account<'info, Mint>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MintNft<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_CONFIG_SEED],
+        bump,
+        constraint = global_config.version == GLOBAL_CONFIG_VERSION @ PresaleError::ConfigNotMigrated,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        mut,
+        seeds = [TIER_SEED, &[tier.id]],
+        bump = tier.bump,
+        constraint = tier.version == TIER_CONFIG_VERSION @ PresaleError::TierNotMigrated,
+    )]
+    pub tier: Account<'info, TierConfig>,
+
+    /// CHECK: Metaplex creator, validated by address = global_config.admin
+    #[account(
+        address = global_config.admin,
+    )]
+    pub admin: UncheckedAccount<'info>,
+
+    /// Treasury PDA receiving the payment
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED],
+        bump,
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    /// CHECK: Mint collections (must match global_config.collection_mint)
+    #[account(
+        address = global_config.collection_mint,
+    )]
+    pub collection_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Metadata аккаунт коллекции
+    #[account(mut)]
+    pub collection_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Master edition коллекции
+    #[account(mut)]
+    pub collection_master_edition: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        mint::decimals = 0,
+        mint::authority = payer,
+        mint::freeze_authority = payer,
+    )]
+    pub mint: Account<'info, Mint>,
+
+    /// CHECK: wallet receiving the NFT; the payer itself, or someone else for a gift
+    pub recipient: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = recipient,
+    )]
+    pub recipient_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = NFT_RECORD_SPACE,
+        seeds = [NFT_RECORD_SEED, mint.key().as_ref()],
+        bump,
+    )]
+    pub nft_record: Account<'info, NftRecord>,
+
+    /// CHECK: Metaplex metadata account PDA for this mint
+    #[account(mut)]
+    pub metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Metaplex master edition PDA for this mint
+    #[account(mut)]
+    pub edition: UncheckedAccount<'info>,
+
+    /// CHECK: Metaplex Token Metadata program
+    pub token_metadata_program: Program<'info, Metadata>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+
+    /// CHECK: Instructions sysvar (address-checked), required when the tier's anti-bot mode is on
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: Option<UncheckedAccount<'info>>,
+
+    /// Required when global_config.rate_limited is set
+    #[account(
+        mut,
+        seeds = [RATE_LIMIT_SEED, &[GLOBAL_RATE_LIMIT_SCOPE]],
+        bump = global_rate_limiter.bump,
+    )]
+    pub global_rate_limiter: Option<Account<'info, RateLimiter>>,
+
+    /// Required when tier.rate_limited is set
+    #[account(
+        mut,
+        seeds = [RATE_LIMIT_SEED, &[tier.id]],
+        bump = tier_rate_limiter.bump,
+    )]
+    pub tier_rate_limiter: Option<Account<'info, RateLimiter>>,
+
+    /// Recipient's KYC registry entry, required when tier.kyc_required is set
+    #[account(
+        seeds = [KYC_SEED, recipient.key().as_ref()],
+        bump = kyc_record.bump,
+    )]
+    pub kyc_record: Option<Account<'info, KycRecord>>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpgradeNft<'info> {
+    /// Holder of the NFT being upgraded, pays the delta and new accounts
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_CONFIG_SEED],
+        bump,
+        constraint = global_config.version == GLOBAL_CONFIG_VERSION @ PresaleError::ConfigNotMigrated,
+    )]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        mut,
+        seeds = [TIER_SEED, &[from_tier.id]],
+        bump = from_tier.bump,
+        constraint = from_tier.version == TIER_CONFIG_VERSION @ PresaleError::TierNotMigrated,
+    )]
+    pub from_tier: Box<Account<'info, TierConfig>>,
+
+    #[account(
+        mut,
+        seeds = [TIER_SEED, &[to_tier.id]],
+        bump = to_tier.bump,
+        constraint = to_tier.version == TIER_CONFIG_VERSION @ PresaleError::TierNotMigrated,
+    )]
+    pub to_tier: Box<Account<'info, TierConfig>>,
+
+    /// CHECK: Metaplex creator, validated by address = global_config.admin
+    #[account(
+        address = global_config.admin,
+    )]
+    pub admin: UncheckedAccount<'info>,
+
+    /// Treasury PDA receiving the price delta
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED],
+        bump,
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    // ---- NFT being burned ----
+    #[account(mut)]
+    pub old_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        seeds = [NFT_RECORD_SEED, old_mint.key().as_ref()],
+        bump = old_nft_record.bump,
+    )]
+    pub old_nft_record: Box<Account<'info, NftRecord>>,
+
+    /// CHECK: ClaimRecord address of old_mint, must not exist (see upgrade_nft)
+    #[account(
+        seeds = [CLAIM_SEED, old_mint.key().as_ref()],
+        bump,
+    )]
+    pub old_claim_record: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        token::mint = old_mint,
+        token::authority = owner,
+        constraint = old_token_account.amount == 1 @ PresaleError::InvalidPresaleNft,
+    )]
+    pub old_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), old_mint.key().as_ref()],
+        seeds::program = token_metadata_program.key(),
+        bump,
+    )]
+    pub old_metadata: Box<Account<'info, MetadataAccount>>,
+
+    /// CHECK: Metaplex master edition of old_mint (checked by burn_nft)
+    #[account(mut)]
+    pub old_edition: UncheckedAccount<'info>,
+
+    // ---- Collection ----
+    /// CHECK: Mint collections (must match global_config.collection_mint)
+    #[account(
+        address = global_config.collection_mint,
+    )]
+    pub collection_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Metadata account collection
+    #[account(mut)]
+    pub collection_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Master Edition Collection
+    #[account(mut)]
+    pub collection_master_edition: UncheckedAccount<'info>,
+
+    // ---- New NFT ----
+    #[account(
+        init,
+        payer = owner,
+        mint::decimals = 0,
+        mint::authority = owner,
+        mint::freeze_authority = owner,
+    )]
+    pub mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = owner,
+        associated_token::mint = mint,
+        associated_token::authority = owner,
+    )]
+    pub owner_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = NFT_RECORD_SPACE,
+        seeds = [NFT_RECORD_SEED, mint.key().as_ref()],
+        bump,
+    )]
+    pub nft_record: Box<Account<'info, NftRecord>>,
+
+    /// CHECK: Metaplex metadata account PDA for the new mint
+    #[account(mut)]
+    pub metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Metaplex master edition PDA for the new mint
+    #[account(mut)]
+    pub edition: UncheckedAccount<'info>,
+
+    /// CHECK: Metaplex Token Metadata program
+    pub token_metadata_program: Program<'info, Metadata>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+
+    /// Owner's KYC registry entry, required when to_tier.kyc_required is set
+    #[account(
+        seeds = [KYC_SEED, owner.key().as_ref()],
+        bump = kyc_record.bump,
+    )]
+    pub kyc_record: Option<Account<'info, KycRecord>>,
+
+    /// CHECK: Instructions sysvar (address-checked), required when to_tier's anti-bot mode is on
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: Option<UncheckedAccount<'info>>,
+
+    /// Required when global_config.rate_limited is set
+    #[account(
+        mut,
+        seeds = [RATE_LIMIT_SEED, &[GLOBAL_RATE_LIMIT_SCOPE]],
+        bump = global_rate_limiter.bump,
+    )]
+    pub global_rate_limiter: Option<Box<Account<'info, RateLimiter>>>,
+
+    /// Required when to_tier.rate_limited is set
+    #[account(
+        mut,
+        seeds = [RATE_LIMIT_SEED, &[to_tier.id]],
+        bump = tier_rate_limiter.bump,
+    )]
+    pub tier_rate_limiter: Option<Box<Account<'info, RateLimiter>>>,
+}
+
+#[derive(Accounts)]
+pub struct MintWs20<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AdminMint<'info> {
+    /// Admin or minter role key, pays for the new accounts
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: admin = creator, validated by has_one
+    pub admin: UncheckedAccount<'info>,
+
+    /// CHECK: wallet receiving the grant (team, partner, WS-20 holder, ...)
+    pub recipient: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_CONFIG_SEED],
+        bump,
+        constraint = global_config.version == GLOBAL_CONFIG_VERSION @ PresaleError::ConfigNotMigrated,
+        constraint = global_config.has_role(Role::Minter, authority.key()) @ PresaleError::Unauthorized,
+        has_one = admin,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        mut,
+        seeds = [TIER_SEED, &[tier.id]],
+        bump = tier.bump,
+        constraint = tier.version == TIER_CONFIG_VERSION @ PresaleError::TierNotMigrated,
+    )]
+    pub tier: Account<'info, TierConfig>,
+
+    /// CHECK: Mint collections (must match global_config.collection_mint)
+    #[account(
+        address = global_config.collection_mint,
+    )]
+    pub collection_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Metadata account collection
+    #[account(mut)]
+    pub collection_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Master Edition Collection
+    #[account(mut)]
+    pub collection_master_edition: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        mint::decimals = 0,
+        mint::authority = authority,
+        mint::freeze_authority = authority,
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        associated_token::mint = mint,
+        associated_token::authority = recipient,
+    )]
+    pub recipient_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = NFT_RECORD_SPACE,
+        seeds = [NFT_RECORD_SEED, mint.key().as_ref()],
+        bump,
+    )]
+    pub nft_record: Account<'info, NftRecord>,
+
+    /// CHECK: Metaplex metadata account PDA for this mint
+    #[account(mut)]
+    pub metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Metaplex master edition PDA for this mint
+    #[account(mut)]
+    pub edition: UncheckedAccount<'info>,
+
+    /// CHECK: Metaplex Token Metadata program
+    pub token_metadata_program: Program<'info, Metadata>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AdminAirdrop<'info> {
+    /// Admin or minter role key, pays for every new account
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: admin = creator, validated by has_one
+    pub admin: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_CONFIG_SEED],
+        bump,
+        constraint = global_config.version == GLOBAL_CONFIG_VERSION @ PresaleError::ConfigNotMigrated,
+        constraint = global_config.has_role(Role::Minter, authority.key()) @ PresaleError::Unauthorized,
+        has_one = admin,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        mut,
+        seeds = [TIER_SEED, &[tier.id]],
+        bump = tier.bump,
+        constraint = tier.version == TIER_CONFIG_VERSION @ PresaleError::TierNotMigrated,
+    )]
+    pub tier: Account<'info, TierConfig>,
+
+    /// CHECK: Mint collections (must match global_config.collection_mint)
+    #[account(
+        address = global_config.collection_mint,
+    )]
+    pub collection_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Metadata account collection
+    #[account(mut)]
+    pub collection_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Master Edition Collection
+    #[account(mut)]
+    pub collection_master_edition: UncheckedAccount<'info>,
+
+    /// CHECK: Metaplex Token Metadata program
+    pub token_metadata_program: Program<'info, Metadata>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(args: CreateTierArgs)]
+pub struct CreateTier<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_CONFIG_SEED],
+        bump,
+        constraint = global_config.version == GLOBAL_CONFIG_VERSION @ PresaleError::ConfigNotMigrated,
+        has_one = admin,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = TIER_CONFIG_SPACE,
+        seeds = [TIER_SEED, &[args.tier_id]],
+        bump,
+    )]
+    pub tier: Account<'info, TierConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseTier<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_CONFIG_SEED],
+        bump,
+        constraint = global_config.version == GLOBAL_CONFIG_VERSION @ PresaleError::ConfigNotMigrated,
+        has_one = admin,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [TIER_SEED, &[tier.id]],
+        bump = tier.bump,
+    )]
+    pub tier: Account<'info, TierConfig>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateTier<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_CONFIG_SEED],
+        bump,
+        constraint = global_config.version == GLOBAL_CONFIG_VERSION @ PresaleError::ConfigNotMigrated,
+        has_one = admin,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        mut,
+        seeds = [TIER_SEED, &[tier.id]],
+        bump = tier.bump,
+    )]
+    pub tier: Account<'info, TierConfig>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateConfig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// CHECK: deserialized by hand in migrate_config, older layouts do not
+    /// match the current GlobalConfig (discriminator + admin are checked there)
+    #[account(
+        mut,
+        seeds = [GLOBAL_CONFIG_SEED],
+        bump,
+        owner = crate::ID,
+    )]
+    pub global_config: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+    // remaining_accounts (v0 only): the six tier PDAs, writable, in TierId order
+}
+
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_CONFIG_SEED],
+        bump,
+        constraint = global_config.version == GLOBAL_CONFIG_VERSION @ PresaleError::ConfigNotMigrated,
+        has_one = admin,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    /// Must be the key stored in global_config.pending_admin
+    pub new_admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_CONFIG_SEED],
+        bump,
+        constraint = global_config.version == GLOBAL_CONFIG_VERSION @ PresaleError::ConfigNotMigrated,
+        constraint = global_config.pending_admin == new_admin.key() @ PresaleError::NoPendingAdmin,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+}
+
+#[derive(Accounts)]
+pub struct CancelAdminTransfer<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_CONFIG_SEED],
+        bump,
+        constraint = global_config.version == GLOBAL_CONFIG_VERSION @ PresaleError::ConfigNotMigrated,
+        has_one = admin,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+}
+
+#[derive(Accounts)]
+pub struct SetRole<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_CONFIG_SEED],
+        bump,
+        constraint = global_config.version == GLOBAL_CONFIG_VERSION @ PresaleError::ConfigNotMigrated,
+        has_one = admin,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawTreasury<'info> {
+    /// Admin or treasury withdrawer role key (checked in withdraw_treasury)
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_CONFIG_SEED],
+        bump,
+        constraint = global_config.version == GLOBAL_CONFIG_VERSION @ PresaleError::ConfigNotMigrated,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED],
+        bump,
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    /// CHECK: any wallet chosen by the withdrawer, only receives lamports
+    #[account(mut)]
+    pub destination: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetMultisig<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_CONFIG_SEED],
+        bump,
+        constraint = global_config.version == GLOBAL_CONFIG_VERSION @ PresaleError::ConfigNotMigrated,
+        has_one = admin,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeAction<'info> {
+    /// Multisig signer (checked in propose_action), pays for the proposal
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_CONFIG_SEED],
+        bump,
+        constraint = global_config.version == GLOBAL_CONFIG_VERSION @ PresaleError::ConfigNotMigrated,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = PROPOSAL_SPACE,
+        seeds = [PROPOSAL_SEED, &global_config.multisig.proposal_count.to_le_bytes()],
+        bump,
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveAction<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_CONFIG_SEED],
+        bump,
+        constraint = global_config.version == GLOBAL_CONFIG_VERSION @ PresaleError::ConfigNotMigrated,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        mut,
+        seeds = [PROPOSAL_SEED, &proposal.id.to_le_bytes()],
+        bump = proposal.bump,
+    )]
+    pub proposal: Account<'info, Proposal>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteAction<'info> {
+    pub executor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_CONFIG_SEED],
+        bump,
+        constraint = global_config.version == GLOBAL_CONFIG_VERSION @ PresaleError::ConfigNotMigrated,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        mut,
+        seeds = [PROPOSAL_SEED, &proposal.id.to_le_bytes()],
+        bump = proposal.bump,
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    // Action-specific accounts (pass only what the proposal needs)
+    #[account(
+        mut,
+        seeds = [TIER_SEED, &[tier.id]],
+        bump = tier.bump,
+        constraint = tier.version == TIER_CONFIG_VERSION @ PresaleError::TierNotMigrated,
+    )]
+    pub tier: Option<Account<'info, TierConfig>>,
+
+    pub new_collection_mint: Option<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED],
+        bump,
+    )]
+    pub treasury: Option<SystemAccount<'info>>,
+
+    /// CHECK: must equal the destination stored in the proposal
+    #[account(mut)]
+    pub destination: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        seeds = [BUYBACK_SEED],
+        bump = buyback_config.bump,
+    )]
+    pub buyback_config: Option<Account<'info, BuybackConfig>>,
+
+    /// CHECK: PDA owning the held NFTs, holds no data
+    #[account(
+        seeds = [BUYBACK_VAULT_SEED],
+        bump,
+    )]
+    pub buyback_vault: Option<UncheckedAccount<'info>>,
+
+    /// Buyback vault ATA of the NFT to release (checked in release_from_buyback_vault)
+    #[account(mut)]
+    pub vault_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub destination_token_account: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Option<Program<'info, Token>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApplyPendingConfig<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_CONFIG_SEED],
+        bump,
+        constraint = global_config.version == GLOBAL_CONFIG_VERSION @ PresaleError::ConfigNotMigrated,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Tier whose pending change should be applied (optional)
+    #[account(
+        mut,
+        seeds = [TIER_SEED, &[tier.id]],
+        bump = tier.bump,
+        constraint = tier.version == TIER_CONFIG_VERSION @ PresaleError::TierNotMigrated,
+    )]
+    pub tier: Option<Account<'info, TierConfig>>,
+}
+
+#[derive(Accounts)]
+pub struct SetConfigDelay<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_CONFIG_SEED],
+        bump,
+        constraint = global_config.version == GLOBAL_CONFIG_VERSION @ PresaleError::ConfigNotMigrated,
+        has_one = admin,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+}
+
+#[derive(Accounts)]
+#[instruction(scope: u8)]
+pub struct SetRateLimit<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_CONFIG_SEED],
+        bump,
+        constraint = global_config.version == GLOBAL_CONFIG_VERSION @ PresaleError::ConfigNotMigrated,
+        has_one = admin,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Tier to throttle (required unless scope is GLOBAL_RATE_LIMIT_SCOPE)
+    #[account(
+        mut,
+        seeds = [TIER_SEED, &[tier.id]],
+        bump = tier.bump,
+        constraint = tier.version == TIER_CONFIG_VERSION @ PresaleError::TierNotMigrated,
+    )]
+    pub tier: Option<Account<'info, TierConfig>>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = RATE_LIMITER_SPACE,
+        seeds = [RATE_LIMIT_SEED, &[scope]],
+        bump,
+    )]
+    pub rate_limiter: Account<'info, RateLimiter>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetTierAllocation<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_CONFIG_SEED],
+        bump,
+        constraint = global_config.version == GLOBAL_CONFIG_VERSION @ PresaleError::ConfigNotMigrated,
+        has_one = admin,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        seeds = [TIER_SEED, &[tier.id]],
+        bump = tier.bump,
+        constraint = tier.version == TIER_CONFIG_VERSION @ PresaleError::TierNotMigrated,
+    )]
+    pub tier: Account<'info, TierConfig>,
+
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = TIER_ALLOCATION_SPACE,
+        seeds = [ALLOCATION_SEED, &[tier.id]],
+        bump,
+    )]
+    pub allocation: Account<'info, TierAllocation>,
+
+    /// Vault holding the claimable tokens (authority = itself)
+    #[account(
+        init_if_needed,
+        payer = admin,
+        token::mint = token_mint,
+        token::authority = vault,
+        seeds = [ALLOCATION_VAULT_SEED, token_mint.key().as_ref()],
+        bump,
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimAllocation<'info> {
+    /// Current holder of the NFT, pays for the claim record
+    #[account(mut)]
+    pub holder: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_CONFIG_SEED],
+        bump,
+        constraint = global_config.version == GLOBAL_CONFIG_VERSION @ PresaleError::ConfigNotMigrated,
+    )]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        seeds = [TIER_SEED, &[tier.id]],
+        bump = tier.bump,
+        constraint = tier.version == TIER_CONFIG_VERSION @ PresaleError::TierNotMigrated,
+    )]
+    pub tier: Box<Account<'info, TierConfig>>,
+
+    #[account(
+        mut,
+        seeds = [ALLOCATION_SEED, &[tier.id]],
+        bump = allocation.bump,
+        has_one = token_mint,
+    )]
+    pub allocation: Box<Account<'info, TierAllocation>>,
+
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [ALLOCATION_VAULT_SEED, token_mint.key().as_ref()],
+        bump,
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = holder,
+        associated_token::mint = token_mint,
+        associated_token::authority = holder,
+    )]
+    pub holder_token_account: Box<Account<'info, TokenAccount>>,
+
+    // ---- NFT presented for the claim ----
+    pub nft_mint: Box<Account<'info, Mint>>,
+
+    /// Written at mint time, ties the NFT to its tier and serial
+    #[account(
+        seeds = [NFT_RECORD_SEED, nft_mint.key().as_ref()],
+        bump = nft_record.bump,
+    )]
+    pub nft_record: Box<Account<'info, NftRecord>>,
+
+    #[account(
+        token::mint = nft_mint,
+        token::authority = holder,
+        constraint = nft_token_account.amount == 1 @ PresaleError::InvalidPresaleNft,
+    )]
+    pub nft_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), nft_mint.key().as_ref()],
+        seeds::program = token_metadata_program.key(),
+        bump,
+    )]
+    pub nft_metadata: Box<Account<'info, MetadataAccount>>,
+
+    /// Created on the first claim of this NFT, reused afterwards
+    #[account(
+        init_if_needed,
+        payer = holder,
+        space = CLAIM_RECORD_SPACE,
+        seeds = [CLAIM_SEED, nft_mint.key().as_ref()],
+        bump,
+    )]
+    pub claim_record: Box<Account<'info, ClaimRecord>>,
+
+    /// CHECK: Metaplex Token Metadata program
+    pub token_metadata_program: Program<'info, Metadata>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct StakeNft<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_CONFIG_SEED],
+        bump,
+        constraint = global_config.version == GLOBAL_CONFIG_VERSION @ PresaleError::ConfigNotMigrated,
+    )]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        seeds = [TIER_SEED, &[tier.id]],
+        bump = tier.bump,
+        constraint = tier.version == TIER_CONFIG_VERSION @ PresaleError::TierNotMigrated,
+    )]
+    pub tier: Box<Account<'info, TierConfig>>,
+
+    /// CHECK: PDA delegate that freezes / thaws staked NFTs, holds no data
+    #[account(
+        seeds = [STAKE_AUTHORITY_SEED],
+        bump,
+    )]
+    pub stake_authority: UncheckedAccount<'info>,
+
+    pub nft_mint: Box<Account<'info, Mint>>,
+
+    /// Written at mint time, ties the NFT to its tier and serial
+    #[account(
+        seeds = [NFT_RECORD_SEED, nft_mint.key().as_ref()],
+        bump = nft_record.bump,
+    )]
+    pub nft_record: Box<Account<'info, NftRecord>>,
+
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = owner,
+        constraint = nft_token_account.amount == 1 @ PresaleError::InvalidPresaleNft,
+    )]
+    pub nft_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), nft_mint.key().as_ref()],
+        seeds::program = token_metadata_program.key(),
+        bump,
+    )]
+    pub nft_metadata: Box<Account<'info, MetadataAccount>>,
+
+    /// CHECK: Metaplex master edition of nft_mint (checked by freeze_delegated_account)
+    pub nft_edition: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = STAKE_RECORD_SPACE,
+        seeds = [STAKE_SEED, nft_mint.key().as_ref()],
+        bump,
+    )]
+    pub stake_record: Box<Account<'info, StakeRecord>>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = STAKER_POINTS_SPACE,
+        seeds = [STAKER_POINTS_SEED, owner.key().as_ref()],
+        bump,
+    )]
+    pub staker_points: Box<Account<'info, StakerPoints>>,
+
+    /// CHECK: Metaplex Token Metadata program
+    pub token_metadata_program: Program<'info, Metadata>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UnstakeNft<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [TIER_SEED, &[tier.id]],
+        bump = tier.bump,
+        constraint = tier.version == TIER_CONFIG_VERSION @ PresaleError::TierNotMigrated,
+        constraint = tier.id == stake_record.tier_id @ PresaleError::InvalidTierId,
+    )]
+    pub tier: Box<Account<'info, TierConfig>>,
+
+    /// CHECK: PDA delegate that freezes / thaws staked NFTs, holds no data
+    #[account(
+        seeds = [STAKE_AUTHORITY_SEED],
+        bump,
+    )]
+    pub stake_authority: UncheckedAccount<'info>,
+
+    pub nft_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = owner,
+    )]
+    pub nft_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Metaplex metadata of nft_mint (checked by thaw_delegated_account)
+    pub nft_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Metaplex master edition of nft_mint (checked by thaw_delegated_account)
+    pub nft_edition: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [STAKE_SEED, nft_mint.key().as_ref()],
+        bump = stake_record.bump,
+        has_one = owner,
+    )]
+    pub stake_record: Box<Account<'info, StakeRecord>>,
+
+    #[account(
+        mut,
+        seeds = [STAKER_POINTS_SEED, owner.key().as_ref()],
+        bump = staker_points.bump,
+    )]
+    pub staker_points: Box<Account<'info, StakerPoints>>,
+
+    /// CHECK: Metaplex Token Metadata program
+    pub token_metadata_program: Program<'info, Metadata>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct RequestRedemption<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_CONFIG_SEED],
+        bump,
+        constraint = global_config.version == GLOBAL_CONFIG_VERSION @ PresaleError::ConfigNotMigrated,
+    )]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        seeds = [TIER_SEED, &[tier.id]],
+        bump = tier.bump,
+        constraint = tier.version == TIER_CONFIG_VERSION @ PresaleError::TierNotMigrated,
+    )]
+    pub tier: Box<Account<'info, TierConfig>>,
+
+    /// CHECK: PDA delegate that locks NFTs under redemption, holds no data
+    #[account(
+        seeds = [REDEMPTION_AUTHORITY_SEED],
+        bump,
+    )]
+    pub redemption_authority: UncheckedAccount<'info>,
+
+    pub nft_mint: Box<Account<'info, Mint>>,
+
+    /// Written at mint time, ties the NFT to its tier and serial
+    #[account(
+        seeds = [NFT_RECORD_SEED, nft_mint.key().as_ref()],
+        bump = nft_record.bump,
+    )]
+    pub nft_record: Box<Account<'info, NftRecord>>,
+
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = owner,
+        constraint = nft_token_account.amount == 1 @ PresaleError::InvalidPresaleNft,
+    )]
+    pub nft_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), nft_mint.key().as_ref()],
+        seeds::program = token_metadata_program.key(),
+        bump,
+    )]
+    pub nft_metadata: Box<Account<'info, MetadataAccount>>,
+
+    /// CHECK: Metaplex master edition of nft_mint (checked by freeze_delegated_account)
+    pub nft_edition: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = REDEMPTION_SPACE,
+        seeds = [REDEMPTION_SEED, nft_mint.key().as_ref()],
+        bump,
+    )]
+    pub redemption: Box<Account<'info, Redemption>>,
+
+    /// CHECK: Metaplex Token Metadata program
+    pub token_metadata_program: Program<'info, Metadata>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetRedemptionStatus<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_CONFIG_SEED],
+        bump,
+        constraint = global_config.version == GLOBAL_CONFIG_VERSION @ PresaleError::ConfigNotMigrated,
+        has_one = admin,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
 
-    // Metaplex name limit is strict; keep it short and deterministic
-    let serial6 = format!("{:06}", serial);
-    let name = format!("VIGRI {} NFT #{}", label, serial6);
+    #[account(
+        mut,
+        seeds = [REDEMPTION_SEED, redemption.nft_mint.as_ref()],
+        bump = redemption.bump,
+    )]
+    pub redemption: Account<'info, Redemption>,
+}
 
-    Ok(name)
+#[derive(Accounts)]
+pub struct CancelRedemption<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: PDA delegate that locks NFTs under redemption, holds no data
+    #[account(
+        seeds = [REDEMPTION_AUTHORITY_SEED],
+        bump,
+    )]
+    pub redemption_authority: UncheckedAccount<'info>,
+
+    pub nft_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = owner,
+    )]
+    pub nft_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Metaplex metadata of nft_mint (checked by thaw_delegated_account)
+    pub nft_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Metaplex master edition of nft_mint (checked by thaw_delegated_account)
+    pub nft_edition: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [REDEMPTION_SEED, nft_mint.key().as_ref()],
+        bump = redemption.bump,
+        has_one = owner,
+    )]
+    pub redemption: Box<Account<'info, Redemption>>,
+
+    /// CHECK: Metaplex Token Metadata program
+    pub token_metadata_program: Program<'info, Metadata>,
+
+    pub token_program: Program<'info, Token>,
 }
 
-#[event]
-pub struct NftMinted {
-    pub tier_id: u8,
-    pub serial: u16,
-    pub design_key: u16,
-    pub mint: Pubkey,
+#[derive(Accounts)]
+pub struct CompleteRedemption<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [TIER_SEED, &[tier.id]],
+        bump = tier.bump,
+        constraint = tier.version == TIER_CONFIG_VERSION @ PresaleError::TierNotMigrated,
+        constraint = tier.id == redemption.tier_id @ PresaleError::InvalidTierId,
+    )]
+    pub tier: Box<Account<'info, TierConfig>>,
+
+    /// CHECK: PDA delegate that locks NFTs under redemption, holds no data
+    #[account(
+        seeds = [REDEMPTION_AUTHORITY_SEED],
+        bump,
+    )]
+    pub redemption_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub nft_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = owner,
+    )]
+    pub nft_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Metaplex metadata of nft_mint (checked by burn_nft)
+    #[account(mut)]
+    pub nft_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Metaplex master edition of nft_mint (checked by burn_nft)
+    #[account(mut)]
+    pub nft_edition: UncheckedAccount<'info>,
+
+    /// CHECK: Metadata account collection (its size is decremented by burn_nft)
+    #[account(mut)]
+    pub collection_metadata: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [REDEMPTION_SEED, nft_mint.key().as_ref()],
+        bump = redemption.bump,
+        has_one = owner,
+    )]
+    pub redemption: Box<Account<'info, Redemption>>,
+
+    /// CHECK: Metaplex Token Metadata program
+    pub token_metadata_program: Program<'info, Metadata>,
+
+    pub token_program: Program<'info, Token>,
 }
 
-fn resolve_design_key(tier_id: u8, serial: u16, design_choice: Option<u8>) -> Result<u16> {
-    match tier_id {
-        0 => match design_choice {
-            Some(1) => Ok(1), // TR
-            Some(2) => Ok(2), // FE
-            _ => err!(PresaleError::InvalidDesignChoice),
-        },
-        1 => Ok(1),                     // CU
-        2 => Ok(((serial - 1) % 10) + 1), // AG: 1..10
-        3 => Ok(serial),                // AU
-        4 => Ok(serial),                // PT
-        5 => Ok(serial),                // WS
-        _ => err!(PresaleError::InvalidTierId),
-    }
+#[derive(Accounts)]
+pub struct CompleteOverdueRedemption<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_CONFIG_SEED],
+        bump,
+        constraint = global_config.version == GLOBAL_CONFIG_VERSION @ PresaleError::ConfigNotMigrated,
+        has_one = admin,
+    )]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        mut,
+        seeds = [TIER_SEED, &[tier.id]],
+        bump = tier.bump,
+        constraint = tier.version == TIER_CONFIG_VERSION @ PresaleError::TierNotMigrated,
+        constraint = tier.id == redemption.tier_id @ PresaleError::InvalidTierId,
+    )]
+    pub tier: Box<Account<'info, TierConfig>>,
+
+    /// CHECK: PDA delegate that locks NFTs under redemption, holds no data
+    #[account(
+        seeds = [REDEMPTION_AUTHORITY_SEED],
+        bump,
+    )]
+    pub redemption_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub nft_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = redemption.owner,
+    )]
+    pub nft_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Metaplex metadata of nft_mint (checked by thaw_delegated_account)
+    pub nft_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Metaplex master edition of nft_mint (checked by thaw_delegated_account)
+    pub nft_edition: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [REDEMPTION_SEED, nft_mint.key().as_ref()],
+        bump = redemption.bump,
+    )]
+    pub redemption: Box<Account<'info, Redemption>>,
+
+    /// CHECK: Metaplex Token Metadata program
+    pub token_metadata_program: Program<'info, Metadata>,
+
+    pub token_program: Program<'info, Token>,
 }
 
-// ---------------------------------------------
-// Instruction argument structs
-// ---------------------------------------------
+#[derive(Accounts)]
+#[instruction(serial: u16)]
+pub struct SetCertificate<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
-pub struct InitializeArgs {
-    pub admin: Pubkey,
-    pub collection_mint: Pubkey,
-    pub payment_mint: Pubkey, // can be placeholder for native SOL in v1
+    #[account(
+        seeds = [GLOBAL_CONFIG_SEED],
+        bump,
+        constraint = global_config.version == GLOBAL_CONFIG_VERSION @ PresaleError::ConfigNotMigrated,
+        has_one = admin,
+    )]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        seeds = [TIER_SEED, &[tier.id]],
+        bump = tier.bump,
+        constraint = tier.version == TIER_CONFIG_VERSION @ PresaleError::TierNotMigrated,
+    )]
+    pub tier: Box<Account<'info, TierConfig>>,
+
+    pub nft_mint: Box<Account<'info, Mint>>,
+
+    /// Written at mint time, ties the NFT to its tier and serial
+    #[account(
+        seeds = [NFT_RECORD_SEED, nft_mint.key().as_ref()],
+        bump = nft_record.bump,
+    )]
+    pub nft_record: Box<Account<'info, NftRecord>>,
+
+    #[account(
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), nft_mint.key().as_ref()],
+        seeds::program = token_metadata_program.key(),
+        bump,
+    )]
+    pub nft_metadata: Box<Account<'info, MetadataAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = CERTIFICATE_SPACE,
+        seeds = [CERTIFICATE_SEED, &[tier.id], &serial.to_le_bytes()],
+        bump,
+    )]
+    pub certificate: Box<Account<'info, Certificate>>,
+
+    /// CHECK: Metaplex Token Metadata program
+    pub token_metadata_program: Program<'info, Metadata>,
+
+    pub system_program: Program<'info, System>,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
-pub struct UpdateConfigArgs {
-    pub is_sales_paused: Option<bool>,
-    // Optional per-tier updates: price, flags, etc.
-    pub tier_id: Option<u8>,
-    pub new_price_lamports: Option<u64>,
-    pub new_kyc_required: Option<bool>,
-    pub new_invite_only: Option<bool>,
-    pub new_transferable: Option<bool>,
+#[derive(Accounts)]
+pub struct RegisterNftRecord<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_CONFIG_SEED],
+        bump,
+        constraint = global_config.version == GLOBAL_CONFIG_VERSION @ PresaleError::ConfigNotMigrated,
+        has_one = admin,
+    )]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        seeds = [TIER_SEED, &[tier.id]],
+        bump = tier.bump,
+        constraint = tier.version == TIER_CONFIG_VERSION @ PresaleError::TierNotMigrated,
+    )]
+    pub tier: Box<Account<'info, TierConfig>>,
+
+    pub nft_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), nft_mint.key().as_ref()],
+        seeds::program = token_metadata_program.key(),
+        bump,
+    )]
+    pub nft_metadata: Box<Account<'info, MetadataAccount>>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = NFT_RECORD_SPACE,
+        seeds = [NFT_RECORD_SEED, nft_mint.key().as_ref()],
+        bump,
+    )]
+    pub nft_record: Box<Account<'info, NftRecord>>,
+
+    /// CHECK: Metaplex Token Metadata program
+    pub token_metadata_program: Program<'info, Metadata>,
+
+    pub system_program: Program<'info, System>,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
-pub struct MintNftArgs {
-    pub tier_id: u8,
-    // Only used for tier_id == 0 (Tree/Steel):
-    // 1 = TR (Tree), 2 = FE (Steel)
-    pub design_choice: Option<u8>,
+#[derive(Accounts)]
+pub struct SetBuybackConfig<'info> {
+    /// Admin or pauser role key (checked per field in set_buyback_config)
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_CONFIG_SEED],
+        bump,
+        constraint = global_config.version == GLOBAL_CONFIG_VERSION @ PresaleError::ConfigNotMigrated,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = BUYBACK_CONFIG_SPACE,
+        seeds = [BUYBACK_SEED],
+        bump,
+    )]
+    pub buyback_config: Account<'info, BuybackConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetBuybackPrice<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_CONFIG_SEED],
+        bump,
+        constraint = global_config.version == GLOBAL_CONFIG_VERSION @ PresaleError::ConfigNotMigrated,
+        has_one = admin,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        mut,
+        seeds = [TIER_SEED, &[tier.id]],
+        bump = tier.bump,
+        constraint = tier.version == TIER_CONFIG_VERSION @ PresaleError::TierNotMigrated,
+    )]
+    pub tier: Account<'info, TierConfig>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseBuybackNft<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_CONFIG_SEED],
+        bump,
+        constraint = global_config.version == GLOBAL_CONFIG_VERSION @ PresaleError::ConfigNotMigrated,
+        has_one = admin,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Treasury PDA, receives the vault ATA rent
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED],
+        bump,
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    pub nft_mint: Box<Account<'info, Mint>>,
+
+    /// CHECK: PDA owning the held NFTs, holds no data
+    #[account(
+        seeds = [BUYBACK_VAULT_SEED],
+        bump,
+    )]
+    pub buyback_vault: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = nft_mint,
+        associated_token::authority = buyback_vault,
+    )]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = nft_mint,
+    )]
+    pub destination_token_account: Box<Account<'info, TokenAccount>>,
 
-    pub kyc_proof: Option<Vec<u8>>,
-    pub invite_proof: Option<Vec<u8>>,
+    pub token_program: Program<'info, Token>,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
-pub struct MintWs20Args {
-    // WS-20 mint requires a special invite proof
-    pub ws_invite_proof: Vec<u8>,
-}
+#[derive(Accounts)]
+pub struct SellBackNft<'info> {
+    /// Holder selling the NFT, receives the buyback price
+    #[account(mut)]
+    pub seller: Signer<'info>,
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
-pub struct AdminMintArgs {
-    pub tier_id: u8,
+    #[account(
+        seeds = [GLOBAL_CONFIG_SEED],
+        bump,
+        constraint = global_config.version == GLOBAL_CONFIG_VERSION @ PresaleError::ConfigNotMigrated,
+    )]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
 
-    // Only used for tier_id == 0 (Tree/Steel):
-    // 1 = TR (Tree), 2 = FE (Steel)
-    pub design_choice: Option<u8>,
-}
+    #[account(
+        mut,
+        seeds = [TIER_SEED, &[tier.id]],
+        bump = tier.bump,
+        constraint = tier.version == TIER_CONFIG_VERSION @ PresaleError::TierNotMigrated,
+    )]
+    pub tier: Box<Account<'info, TierConfig>>,
 
-// ---------------------------------------------
-// Account context structs
-// ---------------------------------------------
+    #[account(
+        mut,
+        seeds = [BUYBACK_SEED],
+        bump = buyback_config.bump,
+    )]
+    pub buyback_config: Box<Account<'info, BuybackConfig>>,
+
+    /// Treasury PDA paying the buyback price
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED],
+        bump,
+    )]
+    pub treasury: SystemAccount<'info>,
 
-#[derive(Accounts)]
-pub struct Initialize<'info> {
     #[account(mut)]
-    pub payer: Signer<'info>,
+    pub nft_mint: Box<Account<'info, Mint>>,
 
-    /// CHECK: authority to be stored as admin in GlobalConfig
-    pub admin: UncheckedAccount<'info>,
+    /// Written at mint time, ties the NFT to its tier and serial
+    #[account(
+        seeds = [NFT_RECORD_SEED, nft_mint.key().as_ref()],
+        bump = nft_record.bump,
+    )]
+    pub nft_record: Box<Account<'info, NftRecord>>,
 
     #[account(
-        init,
-        payer = payer,
-        space = GLOBAL_CONFIG_SPACE,
-        seeds = [GLOBAL_CONFIG_SEED],
+        mut,
+        token::mint = nft_mint,
+        token::authority = seller,
+        constraint = nft_token_account.amount == 1 @ PresaleError::InvalidPresaleNft,
+    )]
+    pub nft_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), nft_mint.key().as_ref()],
+        seeds::program = token_metadata_program.key(),
         bump,
     )]
-    pub global_config: Account<'info, GlobalConfig>,
+    pub nft_metadata: Box<Account<'info, MetadataAccount>>,
 
-    pub system_program: Program<'info, System>,
-}
+    /// CHECK: Metaplex master edition of nft_mint (checked by burn_nft)
+    #[account(mut)]
+    pub nft_edition: UncheckedAccount<'info>,
 
-#[derive(Accounts)]
-pub struct UpdateConfig<'info> {
+    /// CHECK: Metadata account collection (its size is decremented by burn_nft)
     #[account(mut)]
-    pub admin: Signer<'info>,
+    pub collection_metadata: UncheckedAccount<'info>,
 
+    /// CHECK: PDA owning the held NFTs, holds no data
     #[account(
-        mut,
-        seeds = [GLOBAL_CONFIG_SEED],
+        seeds = [BUYBACK_VAULT_SEED],
         bump,
-        has_one = admin,
     )]
-    pub global_config: Account<'info, GlobalConfig>,
+    pub buyback_vault: UncheckedAccount<'info>,
+
+    /// CHECK: buyback vault ATA for nft_mint (required unless burning), created by the ATA program
+    #[account(mut)]
+    pub vault_token_account: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Metaplex Token Metadata program
+    pub token_metadata_program: Program<'info, Metadata>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct UpdateCollectionMint<'info> {
-    #[account(mut)]
+pub struct SetVoucherSigner<'info> {
     pub admin: Signer<'info>,
 
     #[account(
         mut,
         seeds = [GLOBAL_CONFIG_SEED],
         bump,
+        constraint = global_config.version == GLOBAL_CONFIG_VERSION @ PresaleError::ConfigNotMigrated,
         has_one = admin,
     )]
     pub global_config: Account<'info, GlobalConfig>,
-
-    pub new_collection_mint: Account<'info, Mint>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
-pub struct MintNft<'info> {
+#[instruction(voucher: Voucher)]
+pub struct RedeemVoucher<'info> {
+    /// Pays rent for the new accounts (the backend relayer or the buyer)
     #[account(mut)]
     pub payer: Signer<'info>,
 
     #[account(
-        mut,
         seeds = [GLOBAL_CONFIG_SEED],
         bump,
+        constraint = global_config.version == GLOBAL_CONFIG_VERSION @ PresaleError::ConfigNotMigrated,
     )]
-    pub global_config: Account<'info, GlobalConfig>,
+    pub global_config: Box<Account<'info, GlobalConfig>>,
 
-    /// CHECK: Metaplex admin / treasury, validated by address = global_config.admin
     #[account(
         mut,
+        seeds = [TIER_SEED, &[voucher.tier_id]],
+        bump = tier.bump,
+        constraint = tier.version == TIER_CONFIG_VERSION @ PresaleError::TierNotMigrated,
+    )]
+    pub tier: Box<Account<'info, TierConfig>>,
+
+    /// Used-nonce marker; `init` fails if the voucher was already redeemed
+    #[account(
+        init,
+        payer = payer,
+        space = VOUCHER_RECORD_SPACE,
+        seeds = [VOUCHER_SEED, &voucher.nonce.to_le_bytes()],
+        bump,
+    )]
+    pub voucher_record: Account<'info, VoucherRecord>,
+
+    /// CHECK: Metaplex creator, validated by address = global_config.admin
+    #[account(
         address = global_config.admin,
     )]
     pub admin: UncheckedAccount<'info>,
@@ -779,11 +6249,11 @@ pub struct MintNft<'info> {
     )]
     pub collection_mint: UncheckedAccount<'info>,
 
-    /// CHECK: Metadata аккаунт коллекции
+    /// CHECK: Metadata account collection
     #[account(mut)]
     pub collection_metadata: UncheckedAccount<'info>,
 
-    /// CHECK: Master edition коллекции
+    /// CHECK: Master Edition Collection
     #[account(mut)]
     pub collection_master_edition: UncheckedAccount<'info>,
 
@@ -796,13 +6266,28 @@ pub struct MintNft<'info> {
     )]
     pub mint: Account<'info, Mint>,
 
+    /// CHECK: wallet named in the voucher
+    #[account(
+        address = voucher.recipient @ PresaleError::InvalidVoucher,
+    )]
+    pub recipient: UncheckedAccount<'info>,
+
     #[account(
         init,
         payer = payer,
         associated_token::mint = mint,
-        associated_token::authority = payer,
+        associated_token::authority = recipient,
+    )]
+    pub recipient_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = NFT_RECORD_SPACE,
+        seeds = [NFT_RECORD_SEED, mint.key().as_ref()],
+        bump,
     )]
-    pub payer_token_account: Account<'info, TokenAccount>,
+    pub nft_record: Account<'info, NftRecord>,
 
     /// CHECK: Metaplex metadata account PDA for this mint
     #[account(mut)]
@@ -815,6 +6300,10 @@ pub struct MintNft<'info> {
     /// CHECK: Metaplex Token Metadata program
     pub token_metadata_program: Program<'info, Metadata>,
 
+    /// CHECK: Instructions sysvar (address-checked), holds the ed25519 verification
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
@@ -822,72 +6311,54 @@ pub struct MintNft<'info> {
 }
 
 #[derive(Accounts)]
-pub struct MintWs20<'info> {
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    pub system_program: Program<'info, System>,
-}
-
-#[derive(Accounts)]
-pub struct AdminMint<'info> {
-    /// Admin = authority of the program and treasury owner
+#[instruction(wallet: Pubkey)]
+pub struct SetKycRecord<'info> {
+    /// Admin or KYC manager role key, pays for a new entry
     #[account(mut)]
-    pub admin: Signer<'info>,
+    pub authority: Signer<'info>,
 
     #[account(
-        mut,
         seeds = [GLOBAL_CONFIG_SEED],
         bump,
-        has_one = admin,
+        constraint = global_config.version == GLOBAL_CONFIG_VERSION @ PresaleError::ConfigNotMigrated,
+        constraint = global_config.has_role(Role::KycManager, authority.key()) @ PresaleError::Unauthorized,
     )]
     pub global_config: Account<'info, GlobalConfig>,
 
-    /// CHECK: Mint collections (must match global_config.collection_mint)
     #[account(
-        address = global_config.collection_mint,
+        init_if_needed,
+        payer = authority,
+        space = KYC_RECORD_SPACE,
+        seeds = [KYC_SEED, wallet.as_ref()],
+        bump,
     )]
-    pub collection_mint: UncheckedAccount<'info>,
+    pub kyc_record: Account<'info, KycRecord>,
 
-    /// CHECK: Metadata account collection
-    #[account(mut)]
-    pub collection_metadata: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
 
-    /// CHECK: Master Edition Collection
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct RevokeKycRecord<'info> {
+    /// Admin or KYC manager role key, receives the entry's rent
     #[account(mut)]
-    pub collection_master_edition: UncheckedAccount<'info>,
+    pub authority: Signer<'info>,
 
     #[account(
-        init,
-        payer = admin,
-        mint::decimals = 0,
-        mint::authority = admin,
-        mint::freeze_authority = admin,
+        seeds = [GLOBAL_CONFIG_SEED],
+        bump,
+        constraint = global_config.version == GLOBAL_CONFIG_VERSION @ PresaleError::ConfigNotMigrated,
+        constraint = global_config.has_role(Role::KycManager, authority.key()) @ PresaleError::Unauthorized,
     )]
-    pub mint: Account<'info, Mint>,
+    pub global_config: Account<'info, GlobalConfig>,
 
     #[account(
-        init,
-        payer = admin,
-        associated_token::mint = mint,
-        associated_token::authority = admin,
+        mut,
+        close = authority,
+        seeds = [KYC_SEED, wallet.as_ref()],
+        bump = kyc_record.bump,
     )]
-    pub admin_token_account: Account<'info, TokenAccount>,
-
-    /// CHECK: Metaplex metadata account PDA for this mint
-    #[account(mut)]
-    pub metadata: UncheckedAccount<'info>,
-
-    /// CHECK: Metaplex master edition PDA for this mint
-    #[account(mut)]
-    pub edition: UncheckedAccount<'info>,
-
-    /// CHECK: Metaplex Token Metadata program
-    pub token_metadata_program: Program<'info, Metadata>,
-
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
+    pub kyc_record: Account<'info, KycRecord>,
 }
 
 #[error_code]
@@ -910,9 +6381,514 @@ pub enum PresaleError {
     #[msg("Invite is required for this tier")]
     InviteRequired,
 
-    #[msg("Only admin can perform this action")]
+    #[msg("Signer is not allowed to perform this action")]
     Unauthorized,
 
     #[msg("Invalid design choice for this tier")]
     InvalidDesignChoice,
+
+    #[msg("Invalid tier configuration")]
+    InvalidTierConfig,
+
+    #[msg("Tier already has minted NFTs and cannot be closed")]
+    TierHasMints,
+
+    #[msg("GlobalConfig must be migrated with migrate_config first")]
+    ConfigNotMigrated,
+
+    #[msg("GlobalConfig is already at the current version")]
+    ConfigAlreadyMigrated,
+
+    #[msg("Account is not a GlobalConfig")]
+    InvalidConfigAccount,
+
+    #[msg("Invalid admin key")]
+    InvalidAdmin,
+
+    #[msg("No matching admin transfer is pending")]
+    NoPendingAdmin,
+
+    #[msg("Not enough lamports in the treasury")]
+    InsufficientTreasury,
+
+    #[msg("This action must go through a multisig proposal")]
+    MultisigRequired,
+
+    #[msg("Multisig is not configured")]
+    MultisigNotActive,
+
+    #[msg("Invalid multisig signers or threshold")]
+    InvalidMultisig,
+
+    #[msg("Signer is not part of the multisig")]
+    NotMultisigSigner,
+
+    #[msg("Signer already approved this proposal")]
+    AlreadyApproved,
+
+    #[msg("Proposal was already executed")]
+    ProposalAlreadyExecuted,
+
+    #[msg("Multisig signers changed since this proposal was created")]
+    ProposalStale,
+
+    #[msg("Not enough approvals to execute this proposal")]
+    ThresholdNotMet,
+
+    #[msg("Missing or mismatched account for this proposal")]
+    MissingActionAccount,
+
+    #[msg("Invalid delay or effective time for a timelocked change")]
+    InvalidConfigDelay,
+
+    #[msg("No pending change is due yet")]
+    NoPendingChange,
+
+    #[msg("Airdrop accounts must come in complete per-recipient groups")]
+    InvalidAirdropAccounts,
+
+    #[msg("Admin mint quota for this tier is exhausted")]
+    AdminQuotaExceeded,
+
+    #[msg("Supply must stay between the minted count and the tier's supply_max")]
+    InvalidSupply,
+
+    #[msg("Tier supply is locked and cannot be increased")]
+    SupplyLocked,
+
+    #[msg("Sales are paused for this tier")]
+    TierPaused,
+
+    #[msg("Transaction rejected by the tier's anti-bot rules")]
+    AntiBotRejected,
+
+    #[msg("Too many mints in the current slot window, try again shortly")]
+    RateLimited,
+
+    #[msg("Rate limiter account is required for this mint")]
+    MissingRateLimiter,
+
+    #[msg("max_mints must be <= 32 and window_slots > 0")]
+    InvalidRateLimit,
+
+    #[msg("Upgrade target must be a different, more expensive tier")]
+    InvalidUpgrade,
+
+    #[msg("NFT is not a presale NFT of the expected tier")]
+    InvalidPresaleNft,
+
+    #[msg("No token allocation is configured for this tier")]
+    AllocationNotSet,
+
+    #[msg("Allocation token cannot change after claims started")]
+    AllocationLocked,
+
+    #[msg("Cliff and vesting durations must not be negative")]
+    InvalidVestingSchedule,
+
+    #[msg("Nothing unlocked to claim yet")]
+    NothingToClaim,
+
+    #[msg("Redemption is not in a state that allows this step")]
+    InvalidRedemptionStatus,
+
+    #[msg("Certificate does not match the tier, serial or mint")]
+    InvalidCertificate,
+
+    #[msg("Buyback is currently paused")]
+    BuybackPaused,
+
+    #[msg("No buyback price is set for this tier")]
+    BuybackNotOffered,
+
+    #[msg("Buyback budget is exhausted")]
+    BuybackBudgetExhausted,
+
+    #[msg("Buyback vault token account is required when NFTs are held")]
+    MissingBuybackVault,
+
+    #[msg("Voucher redemption is disabled (no voucher signer set)")]
+    VouchersDisabled,
+
+    #[msg("Voucher signature is missing or does not match the voucher")]
+    InvalidVoucher,
+
+    #[msg("Voucher has expired")]
+    VoucherExpired,
+
+    #[msg("KYC entry has expired")]
+    KycExpired,
+
+    #[msg("KYC entry needs a future expiry and an uppercase country code")]
+    InvalidKycRecord,
+
+    #[msg("Migration needs the six tier PDAs in TierId order")]
+    InvalidMigrationAccounts,
+
+    #[msg("Tier must be backfilled with migrate_tier first")]
+    TierNotMigrated,
+
+    #[msg("Tier is already at the current version")]
+    TierAlreadyMigrated,
+
+    #[msg("NFT has claimed allocation tokens and cannot be upgraded")]
+    NftHasClaims,
+
+    #[msg("After claims started the schedule can only unlock more or earlier")]
+    AllocationScheduleLocked,
+
+    #[msg("Shipped redemption is not overdue yet")]
+    RedemptionNotOverdue,
+
+    #[msg("Buyback price cannot exceed the tier's sale price")]
+    InvalidBuybackPrice,
+
+    #[msg("NFT was not paid for on-chain and cannot be sold back")]
+    BuybackNotPaid,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // v0 GlobalConfig: discriminator + 3 pubkeys + pause flag + [TierConfig; 6] + reserved
+    const V0_GLOBAL_CONFIG_SPACE: usize = 8 + 512;
+
+    #[test]
+    fn airdrop_batches_fit_one_transaction_each() {
+        let static_accounts = accounts::AdminAirdrop {
+            authority: Pubkey::default(),
+            admin: Pubkey::default(),
+            global_config: Pubkey::default(),
+            tier: Pubkey::default(),
+            collection_mint: Pubkey::default(),
+            collection_metadata: Pubkey::default(),
+            collection_master_edition: Pubkey::default(),
+            token_metadata_program: Pubkey::default(),
+            token_program: Pubkey::default(),
+            associated_token_program: Pubkey::default(),
+            system_program: Pubkey::default(),
+            rent: Pubkey::default(),
+            event_authority: Pubkey::default(),
+            program: Pubkey::default(),
+        }
+        .to_account_metas(None)
+        .len();
+        // + compute budget program
+        assert_eq!(static_accounts + 1, AIRDROP_STATIC_ACCOUNTS);
+
+        let per_tx = airdrop_recipients_per_tx();
+        assert_eq!(per_tx, 2);
+        assert!(
+            AIRDROP_TX_BASE_SIZE + per_tx * AIRDROP_TX_SIZE_PER_RECIPIENT <= MAX_TRANSACTION_SIZE
+        );
+        assert!(
+            AIRDROP_TX_BASE_SIZE + (per_tx + 1) * AIRDROP_TX_SIZE_PER_RECIPIENT
+                > MAX_TRANSACTION_SIZE
+        );
+
+        assert_eq!(airdrop_batches(&[]).count(), 0);
+
+        // Not a multiple of the batch size: the last batch takes the rest
+        let recipients: Vec<Pubkey> = (0..5).map(|_| Pubkey::new_unique()).collect();
+        let batches: Vec<&[Pubkey]> = airdrop_batches(&recipients).collect();
+        assert_eq!(
+            batches.iter().map(|b| b.len()).collect::<Vec<_>>(),
+            vec![2, 2, 1]
+        );
+        assert_eq!(batches.concat(), recipients);
+
+        assert_eq!(airdrop_batches(&recipients[..4]).count(), 2);
+    }
+
+    // Instructions sysvar for a transaction of `(program_id, data)` entries;
+    // the current instruction index is left at 0
+    fn instructions_sysvar(ixs: &[(Pubkey, Vec<u8>)]) -> Vec<u8> {
+        let borrowed: Vec<_> = ixs
+            .iter()
+            .map(
+                |(program_id, data)| sysvar::instructions::BorrowedInstruction {
+                    program_id,
+                    accounts: Vec::new(),
+                    data,
+                },
+            )
+            .collect();
+        sysvar::instructions::construct_instructions_data(&borrowed)
+    }
+
+    fn run_anti_bot(ixs: &[(Pubkey, Vec<u8>)], max_mints: u8) -> Result<()> {
+        let mut data = instructions_sysvar(ixs);
+        let mut lamports = 0;
+        let owner = sysvar::ID;
+        let info = AccountInfo::new(
+            &sysvar::instructions::ID,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &owner,
+            false,
+            0,
+        );
+        check_anti_bot(&info, max_mints)
+    }
+
+    #[test]
+    fn anti_bot_limits_mints_and_companion_instructions() {
+        let mint = (crate::ID, instruction::MintNft::DISCRIMINATOR.to_vec());
+        let compute_budget = (COMPUTE_BUDGET_PROGRAM_ID, vec![2, 0, 0, 0, 0]);
+        let rejected =
+            |result: Result<()>| result.unwrap_err() == error!(PresaleError::AntiBotRejected);
+
+        assert!(run_anti_bot(&[mint.clone(), compute_budget.clone()], 1).is_ok());
+        assert!(run_anti_bot(&[mint.clone(), mint.clone()], 2).is_ok());
+
+        // max_mints + 1 mint instructions
+        assert!(rejected(run_anti_bot(
+            &[mint.clone(), mint.clone(), mint.clone()],
+            2
+        )));
+
+        // A SystemProgram transfer next to the mint
+        let transfer = (system_program::ID, vec![2, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0]);
+        assert!(rejected(run_anti_bot(&[mint.clone(), transfer], 2)));
+
+        // Any other instruction of this program
+        let airdrop = (crate::ID, instruction::AdminAirdrop::DISCRIMINATOR.to_vec());
+        assert!(rejected(run_anti_bot(&[mint, airdrop], 2)));
+    }
+
+    #[test]
+    fn rate_limiter_allows_max_mints_per_window() {
+        let mut limiter = RateLimiter {
+            scope: 1,
+            bump: 0,
+            max_mints: 0,
+            window_slots: 0,
+            head: 0,
+            recent_slots: [0; MAX_RATE_LIMIT_MINTS],
+        };
+        let throttled =
+            |result: Result<()>| result.unwrap_err() == error!(PresaleError::RateLimited);
+
+        // Off: no limit at all
+        for slot in 1..=20 {
+            limiter.record_mint(slot).unwrap();
+        }
+
+        // 3 mints per 10 slots
+        limiter.configure(3, 10);
+        limiter.record_mint(100).unwrap();
+        limiter.record_mint(101).unwrap();
+        limiter.record_mint(105).unwrap();
+        assert!(throttled(limiter.record_mint(109)));
+
+        // The oldest mint (100) leaves the window at 110, then 101 at 111
+        limiter.record_mint(110).unwrap();
+        assert!(throttled(limiter.record_mint(110)));
+        limiter.record_mint(111).unwrap();
+        assert_eq!(limiter.head, 2);
+        assert_eq!(&limiter.recent_slots[..3], &[110, 111, 105]);
+
+        // Changing max_mints starts again with an empty ring
+        limiter.configure(2, 10);
+        assert_eq!(limiter.head, 0);
+        assert!(limiter.recent_slots.iter().all(|&slot| slot == 0));
+        limiter.record_mint(112).unwrap();
+        limiter.record_mint(112).unwrap();
+        assert!(throttled(limiter.record_mint(113)));
+        limiter.record_mint(122).unwrap();
+    }
+
+    #[test]
+    fn migrates_v0_tiers_with_their_counters() {
+        let mut data = vec![0u8; V0_GLOBAL_CONFIG_SPACE];
+        data[..8].copy_from_slice(GlobalConfig::DISCRIMINATOR);
+
+        let mut entries = Vec::new();
+        for tier_id in TierId::ALL {
+            let preset = CreateTierArgs::for_tier(tier_id);
+            V0TierConfig {
+                id: tier_id as u8,
+                supply_total: preset.supply_total,
+                supply_minted: 7 + tier_id as u16,
+                admin_minted: 1,
+                price_lamports: preset.price_lamports + 1,
+                kyc_required: preset.kyc_required,
+                invite_only: preset.invite_only,
+                transferable: preset.transferable,
+                reserved: [0; 8],
+            }
+            .serialize(&mut entries)
+            .unwrap();
+        }
+        data[GLOBAL_CONFIG_VERSION_OFFSET..][..entries.len()].copy_from_slice(&entries);
+
+        let v0_tiers = read_v0_tiers(&data).unwrap();
+        for (v0, tier_id) in v0_tiers.iter().zip(TierId::ALL) {
+            let tier = TierConfig::from_v0(v0, tier_id, 254);
+            let preset = CreateTierArgs::for_tier(tier_id);
+
+            assert_eq!(tier.id, tier_id as u8);
+            assert_eq!(tier.bump, 254);
+            assert_eq!(tier.supply_minted, 7 + tier_id as u16);
+            assert_eq!(tier.admin_minted, 1);
+            assert_eq!(tier.price_lamports, preset.price_lamports + 1);
+            assert_eq!(tier.supply_total, preset.supply_total);
+            assert_eq!(tier.transferable, preset.transferable);
+            assert_eq!(tier.label, preset.label);
+            assert_eq!(tier.uri_base, preset.uri_base);
+            assert!(tier.admin_quota <= tier.supply_total);
+            assert!(tier.supply_max >= tier.supply_total);
+
+            // The migrated tier must fit the PDA space create_tier uses
+            let mut buf = Vec::new();
+            tier.try_serialize(&mut buf).unwrap();
+            assert!(buf.len() <= TIER_CONFIG_SPACE);
+        }
+    }
+    #[test]
+    fn allocation_schedule_only_moves_in_holders_favour() {
+        let allocation = TierAllocation {
+            tier_id: TierId::Bronze as u8,
+            bump: 255,
+            token_mint: Pubkey::default(),
+            amount_per_nft: 1_000,
+            total_claimed: 1,
+            vesting_start_ts: 1_000,
+            cliff_seconds: 100,
+            vesting_seconds: 1_000,
+            reserved: [0; 8],
+        };
+        let args = |amount_per_nft, vesting_start_ts, cliff_seconds, vesting_seconds| {
+            SetTierAllocationArgs {
+                amount_per_nft,
+                vesting_start_ts,
+                cliff_seconds,
+                vesting_seconds,
+            }
+        };
+
+        // Unchanged, more tokens, earlier cliff end, shorter vesting
+        assert!(allocation.is_unlocked_no_later_by(&args(1_000, 1_000, 100, 1_000)));
+        assert!(allocation.is_unlocked_no_later_by(&args(2_000, 1_000, 100, 1_000)));
+        assert!(allocation.is_unlocked_no_later_by(&args(1_000, 1_050, 0, 1_000)));
+        assert!(allocation.is_unlocked_no_later_by(&args(1_000, 1_000, 100, 0)));
+
+        // Fewer tokens, later cliff end, longer vesting
+        assert!(!allocation.is_unlocked_no_later_by(&args(999, 1_000, 100, 1_000)));
+        assert!(!allocation.is_unlocked_no_later_by(&args(1_000, 1_000, 101, 1_000)));
+        assert!(!allocation.is_unlocked_no_later_by(&args(1_000, 2_000, 0, 1_000)));
+        assert!(!allocation.is_unlocked_no_later_by(&args(1_000, 1_000, 100, 1_001)));
+    }
+    #[test]
+    fn queues_stake_weight_outside_the_stored_pending_change() {
+        let mut tier = TierConfig::new(CreateTierArgs::for_tier(TierId::Bronze), 255);
+
+        tier.queue_change(TierChange {
+            stake_weight: Some(3),
+            effective_at: 100,
+            ..Default::default()
+        });
+        assert_eq!(tier.pending_stake_weight, Some(3));
+        assert!(tier.pending.price_lamports.is_none() && tier.pending.admin_quota.is_none());
+
+        assert!(tier.apply_pending_if_due(99).is_none());
+        assert!(tier.apply_pending_if_due(100).is_some());
+        assert_eq!(tier.stake_weight, 3);
+        assert!(tier.pending_change().is_empty());
+    }
+    #[test]
+    fn reads_tiers_written_before_the_version_field() {
+        // Tier PDA as written when admin_quota was the last field before reserved
+        #[derive(AnchorSerialize)]
+        struct AdminQuotaLayoutTier {
+            id: u8,
+            bump: u8,
+            supply_total: u16,
+            supply_minted: u16,
+            admin_minted: u16,
+            price_lamports: u64,
+            kyc_required: bool,
+            invite_only: bool,
+            transferable: bool,
+            design_variants: u16,
+            label: String,
+            uri_base: String,
+            pending: PendingTierChange,
+            admin_quota: u16,
+            reserved: [u8; 36],
+        }
+
+        let preset = CreateTierArgs::for_tier(TierId::TreeSteel);
+        let mut data = TierConfig::DISCRIMINATOR.to_vec();
+        AdminQuotaLayoutTier {
+            id: preset.tier_id,
+            bump: 254,
+            supply_total: preset.supply_total,
+            supply_minted: 12,
+            admin_minted: 3,
+            price_lamports: preset.price_lamports,
+            kyc_required: preset.kyc_required,
+            invite_only: preset.invite_only,
+            transferable: preset.transferable,
+            design_variants: preset.design_variants,
+            label: preset.label,
+            uri_base: preset.uri_base,
+            pending: PendingTierChange {
+                price_lamports: Some(1),
+                effective_at: 50,
+                admin_quota: Some(7),
+                ..Default::default()
+            },
+            admin_quota: preset.admin_quota,
+            reserved: [0; 36],
+        }
+        .serialize(&mut data)
+        .unwrap();
+        data.resize(TIER_CONFIG_SPACE, 0);
+
+        let tier = TierConfig::try_deserialize(&mut &data[..]).unwrap();
+        assert_eq!(tier.admin_quota, preset.admin_quota);
+        assert_eq!(tier.supply_minted, 12);
+        assert_eq!(tier.version, 0);
+        assert_eq!((tier.supply_max, tier.stake_weight), (0, 0));
+
+        let pending = tier.pending_change();
+        assert_eq!(pending.price_lamports, Some(1));
+        assert_eq!(pending.admin_quota, Some(7));
+        assert_eq!(pending.effective_at, 50);
+        assert!(pending.supply_total.is_none() && pending.stake_weight.is_none());
+    }
+    #[test]
+    fn merges_queued_tier_changes_field_by_field() {
+        let mut tier = TierConfig::new(CreateTierArgs::for_tier(TierId::Silver), 255);
+
+        tier.queue_change(TierChange {
+            price_lamports: Some(11),
+            supply_total: Some(150),
+            effective_at: 200,
+            ..Default::default()
+        });
+        tier.queue_change(TierChange {
+            kyc_required: Some(false),
+            supply_total: Some(180),
+            effective_at: 100,
+            ..Default::default()
+        });
+
+        let pending = tier.pending_change();
+        assert_eq!(pending.price_lamports, Some(11));
+        assert_eq!(pending.kyc_required, Some(false));
+        assert_eq!(pending.supply_total, Some(180));
+        assert_eq!(pending.effective_at, 200);
+
+        assert!(tier.apply_pending_if_due(199).is_none());
+        tier.apply_pending_if_due(200).unwrap();
+        assert_eq!(
+            (tier.price_lamports, tier.kyc_required, tier.supply_total),
+            (11, false, 180)
+        );
+    }
 }
@@ -1,10 +1,10 @@
 // scripts/admin_mint.ts
 // Simple CLI tool to call admin_mint from your local machine.
 // Usage (devnet): 
-//   yarn admin-mint <tierId> <count>
+//   yarn admin-mint <tierId> <count> [recipient]
 // Example:
-//   yarn admin-mint 5 20   // WS-20, 20 NFTs
-//   yarn admin-mint 4 1    // Platinum, 1 NFT
+//   yarn admin-mint 5 20 <wallet>  // WS-20, 20 NFTs straight to <wallet>
+//   yarn admin-mint 4 1            // Platinum, 1 NFT to the admin wallet
 
 import * as anchor from "@coral-xyz/anchor";
 import { PublicKey } from "@solana/web3.js";
@@ -27,8 +27,10 @@ const TOKEN_METADATA_PROGRAM_ID = new PublicKey(
   "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s"
 );
 
-// PDA seed must match GLOBAL_CONFIG_SEED in Rust
+// PDA seeds must match GLOBAL_CONFIG_SEED / TIER_SEED / NFT_RECORD_SEED in Rust
 const GLOBAL_CONFIG_SEED = "vigri-presale-config";
+const TIER_SEED = "vigri-presale-tier";
+const NFT_RECORD_SEED = "vigri-presale-nft";
 
 async function main() {
   const provider = anchor.AnchorProvider.env();
@@ -48,11 +50,22 @@ async function main() {
   const count = parseInt(process.argv[3] ?? "1", 10);
 
   if (Number.isNaN(tierId) || Number.isNaN(count) || count <= 0) {
-    console.log("Usage: yarn admin-mint <tierId> <count>");
+    console.log("Usage: yarn admin-mint <tierId> <count> [recipient]");
     process.exit(1);
   }
 
-  console.log(`admin_mint tierId=${tierId}, count=${count}`);
+  // NFTs are minted directly into the recipient's ATA (no follow-up transfer)
+  const recipient = process.argv[4] ? new PublicKey(process.argv[4]) : wallet;
+
+  console.log(
+    `admin_mint tierId=${tierId}, count=${count}, recipient=${recipient.toBase58()}`
+  );
+
+  const [tierPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from(TIER_SEED), Buffer.from([tierId])],
+    PROGRAM_ID
+  );
+  console.log("TierConfig PDA:", tierPda.toBase58());
 
   const program = new anchor.Program(
     idl as anchor.Idl,
@@ -62,9 +75,9 @@ async function main() {
   for (let i = 0; i < count; i++) {
     const mintKeypair = anchor.web3.Keypair.generate();
 
-    const [adminTokenAccount] = PublicKey.findProgramAddressSync(
+    const [recipientTokenAccount] = PublicKey.findProgramAddressSync(
       [
-        wallet.toBuffer(),
+        recipient.toBuffer(),
         TOKEN_PROGRAM_ID.toBuffer(),
         mintKeypair.publicKey.toBuffer(),
       ],
@@ -80,18 +93,26 @@ async function main() {
       TOKEN_METADATA_PROGRAM_ID
     );
 
+    const [nftRecordPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from(NFT_RECORD_SEED), mintKeypair.publicKey.toBuffer()],
+      PROGRAM_ID
+    );
+
     const designChoice = tierId === 0 ? 1 : null; // 1=TR, 2=FE 
 
     const adminMintArgs = {
-      tierId,
       designChoice,
     };
 
     const adminMintAccounts = {
+      authority: wallet,
       admin: wallet,
       globalConfig: globalConfigPda,
+      tier: tierPda,
       mint: mintKeypair.publicKey,
-      adminTokenAccount,
+      recipient,
+      recipientTokenAccount,
+      nftRecord: nftRecordPda,
       metadata: metadataPda,
       tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
       tokenProgram: TOKEN_PROGRAM_ID,
@@ -102,7 +123,7 @@ async function main() {
 
     console.log(`\n[${i + 1}/${count}] admin_mint...`);
     console.log("mint:", mintKeypair.publicKey.toBase58());
-    console.log("adminTokenAccount:", adminTokenAccount.toBase58());
+    console.log("recipientTokenAccount:", recipientTokenAccount.toBase58());
     console.log("metadata:", metadataPda.toBase58());
 
     const tx = await program.methods
@@ -10,6 +10,465 @@ describe("vigri_nft_presale_minter", () => {
   const program = anchor.workspace
     .vigriNftPresaleMinter as anchor.Program<VigriNftPresaleMinter>;
 
+  // TierConfig PDA (must match TIER_SEED in Rust)
+  const tierPda = (tierId: number) =>
+    anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vigri-presale-tier"), Buffer.from([tierId])],
+      program.programId
+    )[0];
+
+  // update_config arguments that change nothing; tests override what they need
+  const updateConfigArgs = (overrides = {}) => ({
+    isSalesPaused: null,
+    newPriceLamports: null,
+    newKycRequired: null,
+    newInviteOnly: null,
+    newTransferable: null,
+    newAdminQuota: null,
+    newSupplyTotal: null,
+    newStakeWeight: null,
+    lockSupply: false,
+    tierPaused: null,
+    newAntiBotMaxMints: null,
+    effectiveAt: null,
+    ...overrides,
+  });
+
+  // Runs `action` and requires it to fail with the given PresaleError name
+  const expectError = async (action: () => Promise<unknown>, code: string) => {
+    try {
+      await action();
+    } catch (err) {
+      if (!String(err).includes(code)) {
+        throw err;
+      }
+      return;
+    }
+    throw new Error(`expected ${code}, but the transaction succeeded`);
+  };
+
+  // Well-known program IDs on Solana
+  const TOKEN_PROGRAM_ID = new anchor.web3.PublicKey(
+    "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+  );
+  const ASSOCIATED_TOKEN_PROGRAM_ID = new anchor.web3.PublicKey(
+    "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
+  );
+  const TOKEN_METADATA_PROGRAM_ID = new anchor.web3.PublicKey(
+    "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s"
+  );
+
+  const ataFor = (owner: anchor.web3.PublicKey, mint: anchor.web3.PublicKey) =>
+    anchor.web3.PublicKey.findProgramAddressSync(
+      [owner.toBuffer(), TOKEN_PROGRAM_ID.toBuffer(), mint.toBuffer()],
+      ASSOCIATED_TOKEN_PROGRAM_ID
+    )[0];
+
+  const metadataFor = (mint: anchor.web3.PublicKey) =>
+    anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("metadata"),
+        TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+        mint.toBuffer(),
+      ],
+      TOKEN_METADATA_PROGRAM_ID
+    )[0];
+
+  const editionFor = (mint: anchor.web3.PublicKey) =>
+    anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("metadata"),
+        TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+        mint.toBuffer(),
+        Buffer.from("edition"),
+      ],
+      TOKEN_METADATA_PROGRAM_ID
+    )[0];
+
+  // PDA of the program under `seed`, optionally followed by a key
+  const programPda = (seed: string, key?: anchor.web3.PublicKey) =>
+    anchor.web3.PublicKey.findProgramAddressSync(
+      key ? [Buffer.from(seed), key.toBuffer()] : [Buffer.from(seed)],
+      program.programId
+    )[0];
+
+  // Fungible test token (no @solana/spl-token here): raw SPL instructions,
+  // InitializeMint2 = 20, MintTo = 7
+  const createTokenMint = async (decimals: number) => {
+    const admin = provider.wallet.publicKey;
+    const mint = anchor.web3.Keypair.generate();
+    const MINT_SIZE = 82;
+
+    const initMint = new anchor.web3.TransactionInstruction({
+      programId: TOKEN_PROGRAM_ID,
+      keys: [{ pubkey: mint.publicKey, isSigner: false, isWritable: true }],
+      data: Buffer.concat([
+        Buffer.from([20, decimals]),
+        admin.toBuffer(),
+        Buffer.from([0]), // no freeze authority
+      ]),
+    });
+
+    const tx = new anchor.web3.Transaction().add(
+      anchor.web3.SystemProgram.createAccount({
+        fromPubkey: admin,
+        newAccountPubkey: mint.publicKey,
+        lamports:
+          await provider.connection.getMinimumBalanceForRentExemption(MINT_SIZE),
+        space: MINT_SIZE,
+        programId: TOKEN_PROGRAM_ID,
+      }),
+      initMint
+    );
+    await provider.sendAndConfirm(tx, [mint]);
+
+    return mint.publicKey;
+  };
+
+  const mintTokensTo = async (
+    mint: anchor.web3.PublicKey,
+    destination: anchor.web3.PublicKey,
+    amount: number
+  ) => {
+    const data = Buffer.alloc(9);
+    data.writeUInt8(7, 0);
+    data.writeBigUInt64LE(BigInt(amount), 1);
+
+    const tx = new anchor.web3.Transaction().add(
+      new anchor.web3.TransactionInstruction({
+        programId: TOKEN_PROGRAM_ID,
+        keys: [
+          { pubkey: mint, isSigner: false, isWritable: true },
+          { pubkey: destination, isSigner: false, isWritable: true },
+          {
+            pubkey: provider.wallet.publicKey,
+            isSigner: true,
+            isWritable: false,
+          },
+        ],
+        data,
+      })
+    );
+    await provider.sendAndConfirm(tx);
+  };
+
+  // Metaplex accounts of the configured collection
+  const collectionAccounts = async () => {
+    const config = await program.account.globalConfig.fetch(
+      programPda("vigri-presale-config")
+    );
+    return {
+      collectionMint: config.collectionMint,
+      collectionMetadata: metadataFor(config.collectionMint),
+      collectionMasterEdition: editionFor(config.collectionMint),
+    };
+  };
+
+  // Fully unlocked allocation for `tierId` (keeps an existing one and its
+  // token), with the vault topped up by `fundAmount`; returns the token mint
+  const ensureAllocation = async (tierId: number, fundAmount: number) => {
+    const admin = provider.wallet.publicKey;
+    const allocationPda = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vigri-presale-allocation"), Buffer.from([tierId])],
+      program.programId
+    )[0];
+
+    const existing = await program.account.tierAllocation.fetchNullable(
+      allocationPda
+    );
+    const tokenMint = existing?.tokenMint ?? (await createTokenMint(0));
+    const vault = programPda("vigri-presale-vault", tokenMint);
+
+    if (!existing) {
+      await program.methods
+        .setTierAllocation({
+          amountPerNft: new anchor.BN(100),
+          vestingStartTs: new anchor.BN(Math.floor(Date.now() / 1000) - 60),
+          cliffSeconds: new anchor.BN(0),
+          vestingSeconds: new anchor.BN(0),
+        })
+        .accounts({
+          admin,
+          globalConfig: programPda("vigri-presale-config"),
+          tier: tierPda(tierId),
+          tokenMint,
+          allocation: allocationPda,
+          vault,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+
+    await mintTokensTo(tokenMint, vault, fundAmount);
+    return { allocationPda, tokenMint, vault };
+  };
+
+  // NftRecord PDA (must match NFT_RECORD_SEED in Rust)
+  const nftRecordFor = (mint: anchor.web3.PublicKey) =>
+    anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vigri-presale-nft"), mint.toBuffer()],
+      program.programId
+    )[0];
+
+  // admin_mint one NFT of `tierId` into `recipient`'s ATA
+  const adminMintTo = async (
+    tierId: number,
+    recipient: anchor.web3.PublicKey,
+    designChoice: number | null = null
+  ) => {
+    const admin = provider.wallet.publicKey;
+    const mintKeypair = anchor.web3.Keypair.generate();
+
+    const [globalConfigPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vigri-presale-config")],
+      program.programId
+    );
+
+    const tx = await program.methods
+      .adminMint({ designChoice })
+      .accounts({
+        authority: admin,
+        admin,
+        globalConfig: globalConfigPda,
+        tier: tierPda(tierId),
+        mint: mintKeypair.publicKey,
+        recipient,
+        recipientTokenAccount: ataFor(recipient, mintKeypair.publicKey),
+        nftRecord: nftRecordFor(mintKeypair.publicKey),
+        metadata: metadataFor(mintKeypair.publicKey),
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .signers([mintKeypair])
+      .rpc({ commitment: "confirmed" });
+
+    console.log(`admin_mint tier ${tierId} tx:`, tx);
+    return { mint: mintKeypair.publicKey, signature: tx };
+  };
+
+  // mint_nft instruction for one new NFT of `tierId`, paid by `payer` and
+  // delivered to `recipient` (a gift when they differ). Optional accounts are
+  // null unless set in `optional`; the new mint must co-sign.
+  const mintNftIx = async (
+    tierId: number,
+    payer: anchor.web3.PublicKey,
+    recipient: anchor.web3.PublicKey = payer,
+    optional = {}
+  ) => {
+    const mintKeypair = anchor.web3.Keypair.generate();
+    const mint = mintKeypair.publicKey;
+
+    const ix = await program.methods
+      .mintNft({ designChoice: tierId === 0 ? 1 : null, inviteProof: null })
+      .accounts({
+        payer,
+        globalConfig: programPda("vigri-presale-config"),
+        tier: tierPda(tierId),
+        admin: provider.wallet.publicKey,
+        treasury: programPda("vigri-presale-treasury"),
+        ...(await collectionAccounts()),
+        mint,
+        recipient,
+        recipientTokenAccount: ataFor(recipient, mint),
+        nftRecord: nftRecordFor(mint),
+        metadata: metadataFor(mint),
+        edition: editionFor(mint),
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        instructions: null,
+        globalRateLimiter: null,
+        tierRateLimiter: null,
+        kycRecord: null,
+        ...optional,
+      })
+      .instruction();
+
+    return { ix, mintKeypair };
+  };
+
+  // Sends mint_nft instructions (plus `extra` ones) in a single transaction
+  const sendMints = async (
+    mints: { ix: anchor.web3.TransactionInstruction; mintKeypair: anchor.web3.Keypair }[],
+    extra: anchor.web3.TransactionInstruction[] = [],
+    signers: anchor.web3.Keypair[] = []
+  ) => {
+    const tx = new anchor.web3.Transaction();
+    mints.forEach(({ ix }) => tx.add(ix));
+    extra.forEach((ix) => tx.add(ix));
+
+    return provider.sendAndConfirm(
+      tx,
+      [...mints.map(({ mintKeypair }) => mintKeypair), ...signers],
+      { commitment: "confirmed" }
+    );
+  };
+
+  // KYC registry PDA of `wallet` (must match KYC_SEED in Rust)
+  const kycRecordFor = (wallet: anchor.web3.PublicKey) =>
+    programPda("vigri-presale-kyc", wallet);
+
+  // Writes a KYC entry for `wallet` (admin holds the KYC manager role)
+  const setKycRecordFor = async (wallet: anchor.web3.PublicKey, expiresAt: number) => {
+    const tx = await program.methods
+      .setKycRecord(wallet, {
+        level: { basic: {} },
+        expiresAt: new anchor.BN(expiresAt),
+        countryCode: Array.from(Buffer.from("EE")),
+      })
+      .accounts({
+        authority: provider.wallet.publicKey,
+        globalConfig: programPda("vigri-presale-config"),
+        kycRecord: kycRecordFor(wallet),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc({ commitment: "confirmed" });
+
+    console.log("set_kyc_record tx:", tx);
+  };
+
+  const revokeKycRecordFor = async (wallet: anchor.web3.PublicKey) => {
+    const tx = await program.methods
+      .revokeKycRecord(wallet)
+      .accounts({
+        authority: provider.wallet.publicKey,
+        globalConfig: programPda("vigri-presale-config"),
+        kycRecord: kycRecordFor(wallet),
+      })
+      .rpc({ commitment: "confirmed" });
+
+    console.log("revoke_kyc_record tx:", tx);
+  };
+
+  // Anchor's tag in front of emit_cpi! event data (EVENT_IX_TAG_LE)
+  const EVENT_IX_TAG = Buffer.from([0xe4, 0x45, 0xa5, 0x2e, 0x51, 0xcb, 0x9a, 0x1d]);
+
+  // Events of a confirmed transaction: emit! ones come from the logs,
+  // emit_cpi! ones from the program's self-CPIs in the inner instructions
+  const eventsOf = async (signature: string) => {
+    const tx = await provider.connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+
+    const parser = new anchor.EventParser(program.programId, program.coder);
+    const events = [...parser.parseLogs(tx.meta.logMessages)];
+
+    const keys = tx.transaction.message.staticAccountKeys;
+    for (const inner of tx.meta.innerInstructions ?? []) {
+      for (const ix of inner.instructions) {
+        const data = Buffer.from(anchor.utils.bytes.bs58.decode(ix.data));
+        if (
+          keys[ix.programIdIndex].equals(program.programId) &&
+          data.subarray(0, 8).equals(EVENT_IX_TAG)
+        ) {
+          const event = program.coder.events.decode(
+            data.subarray(8).toString("base64")
+          );
+          if (event) {
+            events.push(event);
+          }
+        }
+      }
+    }
+
+    return events;
+  };
+
+  const findEvent = (events, name: string) => {
+    const event = events.find((e) => e.name.toLowerCase() === name.toLowerCase());
+    if (!event) {
+      throw new Error(`${name} event was not emitted`);
+    }
+    return event.data;
+  };
+
+  // Short timelock for fresh test configs, so due changes can be applied
+  const TEST_CONFIG_DELAY_SECONDS = 2;
+
+  // Must match GLOBAL_CONFIG_VERSION / GLOBAL_CONFIG_VERSION_OFFSET in Rust
+  const GLOBAL_CONFIG_VERSION = 9;
+  const GLOBAL_CONFIG_VERSION_OFFSET = 8 + 32 * 3 + 1;
+
+  // Must match TIER_CONFIG_VERSION in Rust
+  const TIER_CONFIG_VERSION = 1;
+
+  // Launch tiers (must match CreateTierArgs::for_tier in Rust)
+  const launchTiers = [
+    {
+      tierId: 0,
+      supplyTotal: 2000,
+      designVariants: 0,
+      label: "Tree/Steel",
+      uriBase: "https://vigri.ee/metadata/nft/tree-steel",
+      kyc: false,
+      invite: false,
+      transferable: true,
+      adminQuota: 100,
+    },
+    {
+      tierId: 1,
+      supplyTotal: 1000,
+      designVariants: 1,
+      label: "Bronze",
+      uriBase: "https://vigri.ee/metadata/nft/bronze/CU",
+      kyc: false,
+      invite: false,
+      transferable: true,
+      adminQuota: 50,
+    },
+    {
+      tierId: 2,
+      supplyTotal: 200,
+      designVariants: 10,
+      label: "Silver",
+      uriBase: "https://vigri.ee/metadata/nft/silver/AG",
+      kyc: true,
+      invite: false,
+      transferable: true,
+      adminQuota: 10,
+    },
+    {
+      tierId: 3,
+      supplyTotal: 100,
+      designVariants: 0,
+      label: "Gold",
+      uriBase: "https://vigri.ee/metadata/nft/gold/AU",
+      kyc: true,
+      invite: false,
+      transferable: true,
+      adminQuota: 5,
+    },
+    {
+      tierId: 4,
+      supplyTotal: 20,
+      designVariants: 0,
+      label: "Platinum",
+      uriBase: "https://vigri.ee/metadata/nft/platinum/PT",
+      kyc: true,
+      invite: false,
+      transferable: true,
+      adminQuota: 1,
+    },
+    {
+      tierId: 5,
+      supplyTotal: 20,
+      designVariants: 0,
+      label: "WS-20",
+      uriBase: "https://vigri.ee/metadata/nft/ws/WS",
+      kyc: true,
+      invite: true,
+      transferable: false,
+      adminQuota: 20,
+    },
+  ];
+
   it("initializes config if needed, sets tier prices and mints one NFT", async () => {
     const admin = provider.wallet.publicKey;
 
@@ -36,6 +495,7 @@ describe("vigri_nft_presale_minter", () => {
         admin,
         collectionMint: admin, // temporary placeholder pubkeys
         paymentMint: admin,
+        configDelaySeconds: new anchor.BN(TEST_CONFIG_DELAY_SECONDS),
       };
 
       const initTx = await program.methods
@@ -49,14 +509,39 @@ describe("vigri_nft_presale_minter", () => {
         "GlobalConfig already exists, skipping initialize. PDA:",
         globalConfigPda.toBase58()
       );
-    }
 
-    // 2) Set non-zero prices for tiers 0–4 via update_config
+      // Bring an older GlobalConfig layout up to date
+      const storedVersion =
+        existingGlobalConfig.data[GLOBAL_CONFIG_VERSION_OFFSET];
 
-    const updateAccounts = {
-      admin,
-      globalConfig: globalConfigPda,
-    };
+      if (storedVersion < GLOBAL_CONFIG_VERSION) {
+        const migrateTx = await program.methods
+          .migrateConfig()
+          .accounts({
+            admin,
+            globalConfig: globalConfigPda,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          // v0 kept tiers inline, migrate_config moves them into tier PDAs
+          .remainingAccounts(
+            storedVersion < 1
+              ? [0, 1, 2, 3, 4, 5].map((tierId) => ({
+                  pubkey: tierPda(tierId),
+                  isSigner: false,
+                  isWritable: true,
+                }))
+              : []
+          )
+          .rpc();
+
+        console.log(
+          `migrate_config v${storedVersion} -> v${GLOBAL_CONFIG_VERSION} tx:`,
+          migrateTx
+        );
+      }
+    }
+
+    // 2) Create tier PDAs that do not exist yet (with launch prices)
 
     const tierPriceConfigs = [
       { tierId: 0, priceLamports: new anchor.BN(500_000_000) },    // 0.5 SOL
@@ -67,31 +552,90 @@ describe("vigri_nft_presale_minter", () => {
       // tier 5 (WS-20) remains 0 SOL
     ];
 
+    for (const t of launchTiers) {
+      const existingTier = await provider.connection.getAccountInfo(
+        tierPda(t.tierId)
+      );
+      if (existingTier) {
+        // Tiers created before admin_quota / supply_max existed need an explicit backfill
+        const tier = await program.account.tierConfig.fetch(tierPda(t.tierId));
+        if (tier.version < TIER_CONFIG_VERSION) {
+          const migrateTierTx = await program.methods
+            .migrateTier(t.adminQuota, t.supplyTotal)
+            .accounts({
+              admin,
+              globalConfig: globalConfigPda,
+              tier: tierPda(t.tierId),
+            })
+            .rpc();
+
+          console.log(`migrate_tier ${t.tierId} tx:`, migrateTierTx);
+        }
+        continue;
+      }
+
+      const createTx = await program.methods
+        .createTier({
+          tierId: t.tierId,
+          supplyTotal: t.supplyTotal,
+          priceLamports:
+            tierPriceConfigs.find((cfg) => cfg.tierId === t.tierId)
+              ?.priceLamports ?? new anchor.BN(0),
+          kycRequired: t.kyc,
+          inviteOnly: t.invite,
+          transferable: t.transferable,
+          designVariants: t.designVariants,
+          label: t.label,
+          uriBase: t.uriBase,
+          adminQuota: t.adminQuota,
+          supplyMax: t.supplyTotal,
+          stakeWeight: 0,
+        })
+        .accounts({
+          admin,
+          globalConfig: globalConfigPda,
+          tier: tierPda(t.tierId),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+
+      console.log(`create_tier ${t.tierId} tx:`, createTx);
+    }
+
+    // 3) Re-queue the launch prices via update_config. Price changes are
+    //    timelocked: they apply after the config delay (lazily on the next
+    //    mint or via apply_pending_config), not in this transaction.
+
     for (const cfg of tierPriceConfigs) {
-      const updateArgs = {
-        isSalesPaused: null,
-        tierId: cfg.tierId,
+      const updateArgs = updateConfigArgs({
         newPriceLamports: cfg.priceLamports,
-        newKycRequired: null,
-        newInviteOnly: null,
-        newTransferable: null,
-      };
+      });
 
       const updateTx = await program.methods
         .updateConfig(updateArgs)
-        .accounts(updateAccounts)
+        .accounts({
+          authority: admin,
+          globalConfig: globalConfigPda,
+          tier: tierPda(cfg.tierId),
+        })
         .rpc();
 
       console.log(
-        `update_config tier ${cfg.tierId} tx:`,
+        `update_config (queued) tier ${cfg.tierId} tx:`,
         updateTx
       );
     }
 
-    // 3) Now mint one NFT for tier 0
+    // 4) Now mint one NFT for tier 0
 
     const payer = provider.wallet.publicKey;
 
+    // Treasury PDA (must match TREASURY_SEED in Rust)
+    const [treasuryPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vigri-presale-treasury")],
+      program.programId
+    );
+
     // Well-known program IDs on Solana
     const TOKEN_PROGRAM_ID = new anchor.web3.PublicKey(
       "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
@@ -106,10 +650,12 @@ describe("vigri_nft_presale_minter", () => {
     // New mint for the NFT
     const mintKeypair = anchor.web3.Keypair.generate();
 
-    // Payer's ATA for this mint
-    const [payerTokenAccount] = anchor.web3.PublicKey.findProgramAddressSync(
+    // Recipient's ATA for this mint (the payer buys for itself here;
+    // pass another wallet as recipient for a gift)
+    const recipient = payer;
+    const [recipientTokenAccount] = anchor.web3.PublicKey.findProgramAddressSync(
       [
-        payer.toBuffer(),
+        recipient.toBuffer(),
         TOKEN_PROGRAM_ID.toBuffer(),
         mintKeypair.publicKey.toBuffer(),
       ],
@@ -127,17 +673,20 @@ describe("vigri_nft_presale_minter", () => {
     );
 
     const mintArgs = {
-      tierId: 0,
-      kycProof: null,
+      designChoice: 1, // 1=TR, 2=FE
       inviteProof: null,
     };
 
     const mintAccounts = {
       payer,
       globalConfig: globalConfigPda,
+      tier: tierPda(0),
       admin,
+      treasury: treasuryPda,
       mint: mintKeypair.publicKey,
-      payerTokenAccount,
+      recipient,
+      recipientTokenAccount,
+      nftRecord: nftRecordFor(mintKeypair.publicKey),
       metadata: metadataPda,
       tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
       tokenProgram: TOKEN_PROGRAM_ID,
@@ -151,7 +700,7 @@ describe("vigri_nft_presale_minter", () => {
       globalConfig: mintAccounts.globalConfig.toBase58(),
       admin: mintAccounts.admin.toBase58(),
       mint: mintAccounts.mint.toBase58(),
-      payerTokenAccount: mintAccounts.payerTokenAccount.toBase58(),
+      recipientTokenAccount: mintAccounts.recipientTokenAccount.toBase58(),
       metadata: mintAccounts.metadata.toBase58(),
     });
 
@@ -190,6 +739,7 @@ describe("vigri_nft_presale_minter", () => {
         admin,
         collectionMint: admin, // temporary placeholder pubkeys
         paymentMint: admin,
+        configDelaySeconds: new anchor.BN(TEST_CONFIG_DELAY_SECONDS),
       };
 
       const initTx = await program.methods
@@ -214,10 +764,11 @@ describe("vigri_nft_presale_minter", () => {
     // New mint for the NFT
     const mintKeypair = anchor.web3.Keypair.generate();
 
-    // Admin's ATA (treasury) for this mint
-    const [adminTokenAccount] = anchor.web3.PublicKey.findProgramAddressSync(
+    // Grant goes straight to the recipient's ATA (here: the admin itself)
+    const recipient = admin;
+    const [recipientTokenAccount] = anchor.web3.PublicKey.findProgramAddressSync(
       [
-        admin.toBuffer(),
+        recipient.toBuffer(),
         TOKEN_PROGRAM_ID.toBuffer(),
         mintKeypair.publicKey.toBuffer(),
       ],
@@ -235,14 +786,18 @@ describe("vigri_nft_presale_minter", () => {
     );
 
     const adminMintArgs = {
-      tierId: 0,
+      designChoice: 1, // 1=TR, 2=FE
     };
 
     const adminMintAccounts = {
+      authority: admin,
       admin,
       globalConfig: globalConfigPda,
+      tier: tierPda(0),
       mint: mintKeypair.publicKey,
-      adminTokenAccount,
+      recipient,
+      recipientTokenAccount,
+      nftRecord: nftRecordFor(mintKeypair.publicKey),
       metadata: metadataPda,
       tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
       tokenProgram: TOKEN_PROGRAM_ID,
@@ -255,7 +810,8 @@ describe("vigri_nft_presale_minter", () => {
       admin: adminMintAccounts.admin.toBase58(),
       globalConfig: adminMintAccounts.globalConfig.toBase58(),
       mint: adminMintAccounts.mint.toBase58(),
-      adminTokenAccount: adminMintAccounts.adminTokenAccount.toBase58(),
+      recipientTokenAccount:
+        adminMintAccounts.recipientTokenAccount.toBase58(),
       metadata: adminMintAccounts.metadata.toBase58(),
     });
 
@@ -268,89 +824,1917 @@ describe("vigri_nft_presale_minter", () => {
     console.log("admin_mint tx:", adminMintTx);
   });
 
-  it("enforces 5% admin mint limit for Platinum", async () => {
-    const admin = provider.wallet.publicKey;
+  it("admin mints a grant straight into another wallet's ATA", async () => {
+    // Fresh wallet: the program creates its ATA, the admin pays
+    const grantee = anchor.web3.Keypair.generate().publicKey;
 
-    const [globalConfigPda] = anchor.web3.PublicKey.findProgramAddressSync(
-      [Buffer.from("vigri-presale-config")],
-      program.programId
+    const { mint, signature } = await adminMintTo(0, grantee, 2);
+
+    const granteeAta = ataFor(grantee, mint);
+    const balance = await provider.connection.getTokenAccountBalance(granteeAta);
+    if (balance.value.amount !== "1") {
+      throw new Error("grant did not reach the grantee's ATA");
+    }
+
+    // Token account owner is bytes 32..64 of the SPL account
+    const ataInfo = await provider.connection.getAccountInfo(granteeAta);
+    const owner = new anchor.web3.PublicKey(ataInfo.data.subarray(32, 64));
+    if (!owner.equals(grantee)) {
+      throw new Error("grant ATA is not owned by the grantee");
+    }
+
+    // NftMinted comes through emit_cpi! and names both sides of the grant
+    const minted = findEvent(await eventsOf(signature), "NftMinted");
+    if (
+      !minted.mint.equals(mint) ||
+      !minted.recipient.equals(grantee) ||
+      !minted.buyer.equals(provider.wallet.publicKey) ||
+      !minted.pricePaid.isZero() ||
+      !("admin" in minted.mintPath) ||
+      !minted.uri.startsWith("https://vigri.ee/metadata/nft/tree-steel/FE/")
+    ) {
+      throw new Error("NftMinted does not describe the grant");
+    }
+  });
+
+  it("mints a gift: the payer pays, the recipient gets the NFT", async () => {
+    const payer = provider.wallet.publicKey;
+    const recipient = anchor.web3.Keypair.generate().publicKey;
+    const treasuryPda = programPda("vigri-presale-treasury");
+    const treeTierId = 0;
+
+    const treasuryBefore = await provider.connection.getBalance(treasuryPda);
+    const payerBefore = await provider.connection.getBalance(payer);
+
+    const gift = await mintNftIx(treeTierId, payer, recipient);
+    const giftTx = await sendMints([gift]);
+    console.log("mint_nft gift tx:", giftTx);
+
+    const mint = gift.mintKeypair.publicKey;
+    const balance = await provider.connection.getTokenAccountBalance(
+      ataFor(recipient, mint)
     );
+    if (balance.value.amount !== "1") {
+      throw new Error("gift did not land in the recipient's ATA");
+    }
 
-    // Platinum tier (id = 4, supply_total = 20, max admin mint = 1)
-    const platinumTierId = 4;
+    // The payer covers the price (plus rent and fees); the recipient pays nothing
+    const minted = findEvent(await eventsOf(giftTx), "NftMinted");
+    const price = minted.pricePaid.toNumber();
+    const treasuryAfter = await provider.connection.getBalance(treasuryPda);
+    const payerAfter = await provider.connection.getBalance(payer);
+    if (
+      !minted.buyer.equals(payer) ||
+      !minted.recipient.equals(recipient) ||
+      treasuryAfter - treasuryBefore !== price ||
+      payerBefore - payerAfter < price ||
+      (await provider.connection.getBalance(recipient)) !== 0
+    ) {
+      throw new Error("gift was not paid by the payer");
+    }
+  });
 
-    const TOKEN_PROGRAM_ID = new anchor.web3.PublicKey(
-      "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+  it("checks the recipient's KYC entry for a gift on a KYC tier", async function () {
+    const payer = provider.wallet.publicKey;
+    const recipient = anchor.web3.Keypair.generate().publicKey;
+    const silverTierId = 2;
+    const expiresAt = Math.floor(Date.now() / 1000) + 24 * 60 * 60;
+
+    const silver = await program.account.tierConfig.fetch(tierPda(silverTierId));
+    if (!silver.kycRequired) {
+      this.skip();
+    }
+
+    // The payer is verified, the recipient is not
+    const payerHadRecord =
+      (await program.account.kycRecord.fetchNullable(kycRecordFor(payer))) !== null;
+    await setKycRecordFor(payer, expiresAt);
+
+    try {
+      // 1) The payer's entry cannot stand in for the recipient's
+      await expectError(
+        async () =>
+          sendMints([
+            await mintNftIx(silverTierId, payer, recipient, {
+              kycRecord: kycRecordFor(payer),
+            }),
+          ]),
+        "ConstraintSeeds"
+      );
+      await expectError(
+        async () => sendMints([await mintNftIx(silverTierId, payer, recipient)]),
+        "KycRequired"
+      );
+
+      // 2) Once the recipient is verified, the payer can gift the Silver
+      if (
+        (await provider.connection.getBalance(payer)) <
+        silver.priceLamports.toNumber() + anchor.web3.LAMPORTS_PER_SOL
+      ) {
+        this.skip();
+      }
+
+      await setKycRecordFor(recipient, expiresAt);
+      const gift = await mintNftIx(silverTierId, payer, recipient, {
+        kycRecord: kycRecordFor(recipient),
+      });
+      console.log("mint_nft Silver gift tx:", await sendMints([gift]));
+
+      const balance = await provider.connection.getTokenAccountBalance(
+        ataFor(recipient, gift.mintKeypair.publicKey)
+      );
+      if (balance.value.amount !== "1") {
+        throw new Error("Silver gift did not land in the recipient's ATA");
+      }
+      await revokeKycRecordFor(recipient);
+    } finally {
+      if (!payerHadRecord) {
+        await revokeKycRecordFor(payer);
+      }
+    }
+  });
+
+  it("refuses KYC tier mints without a valid registry entry", async function () {
+    const payer = provider.wallet.publicKey;
+    const buyer = anchor.web3.Keypair.generate().publicKey;
+    const silverTierId = 2;
+
+    const silver = await program.account.tierConfig.fetch(tierPda(silverTierId));
+    if (!silver.kycRequired) {
+      this.skip();
+    }
+
+    const mintSilver = async (kycRecord: anchor.web3.PublicKey | null) =>
+      sendMints([await mintNftIx(silverTierId, payer, buyer, { kycRecord })]);
+
+    // Expiry is checked against the cluster clock
+    const chainNow = async () =>
+      provider.connection.getBlockTime(await provider.connection.getSlot());
+
+    // 1) No registry entry
+    await expectError(() => mintSilver(null), "KycRequired");
+
+    // 2) Expired entry
+    const expiresAt = (await chainNow()) + 5;
+    await setKycRecordFor(buyer, expiresAt);
+    while ((await chainNow()) <= expiresAt) {
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+    }
+    await expectError(() => mintSilver(kycRecordFor(buyer)), "KycExpired");
+
+    // 3) Renewed, then revoked: the closed entry no longer counts
+    await setKycRecordFor(buyer, (await chainNow()) + 24 * 60 * 60);
+    await revokeKycRecordFor(buyer);
+    await expectError(
+      () => mintSilver(kycRecordFor(buyer)),
+      "AccountNotInitialized"
     );
-    const ASSOCIATED_TOKEN_PROGRAM_ID = new anchor.web3.PublicKey(
-      "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
+    await expectError(() => mintSilver(null), "KycRequired");
+
+    const silverAfter = await program.account.tierConfig.fetch(
+      tierPda(silverTierId)
     );
-    const TOKEN_METADATA_PROGRAM_ID = new anchor.web3.PublicKey(
-      "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s"
+    if (silverAfter.supplyMinted !== silver.supplyMinted) {
+      throw new Error("a refused KYC mint minted an NFT");
+    }
+  });
+
+  it("records tier and serial of a minted NFT", async () => {
+    const admin = provider.wallet.publicKey;
+
+    // Bronze: its uri_base must not decide the tier, the record does
+    const { mint } = await adminMintTo(1, admin);
+
+    const bronze = await program.account.tierConfig.fetch(tierPda(1));
+    const record = await program.account.nftRecord.fetch(nftRecordFor(mint));
+    if (
+      !record.mint.equals(mint) ||
+      record.tierId !== 1 ||
+      record.serial !== bronze.supplyMinted
+    ) {
+      throw new Error("NftRecord does not match the mint");
+    }
+  });
+
+  it("refuses to upgrade an NFT whose allocation was claimed", async function () {
+    const admin = provider.wallet.publicKey;
+    const globalConfigPda = programPda("vigri-presale-config");
+    const bronzeTierId = 1;
+
+    const { allocationPda, tokenMint, vault } = await ensureAllocation(
+      bronzeTierId,
+      1_000
     );
+    const allocation = await program.account.tierAllocation.fetch(allocationPda);
+    if (
+      allocation.amountPerNft.isZero() ||
+      allocation.vestingStartTs.add(allocation.cliffSeconds).toNumber() >
+        Math.floor(Date.now() / 1000)
+    ) {
+      // A leftover schedule from another run has nothing unlocked yet
+      this.skip();
+    }
 
-    const doAdminMintOnce = async () => {
-      const mintKeypair = anchor.web3.Keypair.generate();
+    const { mint: nftMint } = await adminMintTo(bronzeTierId, admin);
 
-      const [adminTokenAccount] =
-        anchor.web3.PublicKey.findProgramAddressSync(
-          [
-            admin.toBuffer(),
-            TOKEN_PROGRAM_ID.toBuffer(),
-            mintKeypair.publicKey.toBuffer(),
-          ],
-          ASSOCIATED_TOKEN_PROGRAM_ID
-        );
+    // 1) First claim creates the NFT's ClaimRecord
+    await program.methods
+      .claimAllocation()
+      .accounts({
+        holder: admin,
+        globalConfig: globalConfigPda,
+        tier: tierPda(bronzeTierId),
+        allocation: allocationPda,
+        tokenMint,
+        vault,
+        holderTokenAccount: ataFor(admin, tokenMint),
+        nftMint,
+        nftRecord: nftRecordFor(nftMint),
+        nftTokenAccount: ataFor(admin, nftMint),
+        nftMetadata: metadataFor(nftMint),
+        claimRecord: programPda("vigri-presale-claim", nftMint),
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
 
-      const [metadataPda] = anchor.web3.PublicKey.findProgramAddressSync(
-        [
-          Buffer.from("metadata"),
-          TOKEN_METADATA_PROGRAM_ID.toBuffer(),
-          mintKeypair.publicKey.toBuffer(),
-        ],
-        TOKEN_METADATA_PROGRAM_ID
-      );
+    // 2) Burning it for a Silver would restart the allocation at zero
+    const newMint = anchor.web3.Keypair.generate();
+    await expectError(
+      () =>
+        program.methods
+          .upgradeNft({ designChoice: null, inviteProof: null })
+          .accounts({
+            owner: admin,
+            globalConfig: globalConfigPda,
+            fromTier: tierPda(bronzeTierId),
+            toTier: tierPda(2),
+            admin,
+            treasury: programPda("vigri-presale-treasury"),
+            oldMint: nftMint,
+            oldNftRecord: nftRecordFor(nftMint),
+            oldClaimRecord: programPda("vigri-presale-claim", nftMint),
+            oldTokenAccount: ataFor(admin, nftMint),
+            oldMetadata: metadataFor(nftMint),
+            oldEdition: editionFor(nftMint),
+            ...(await collectionAccounts()),
+            mint: newMint.publicKey,
+            ownerTokenAccount: ataFor(admin, newMint.publicKey),
+            nftRecord: nftRecordFor(newMint.publicKey),
+            metadata: metadataFor(newMint.publicKey),
+            edition: editionFor(newMint.publicKey),
+            tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            systemProgram: anchor.web3.SystemProgram.programId,
+            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+            kycRecord: null,
+            instructions: null,
+            globalRateLimiter: null,
+            tierRateLimiter: null,
+          })
+          .signers([newMint])
+          .rpc(),
+      "NftHasClaims"
+    );
+  });
 
-      const adminMintArgs = {
-        tierId: platinumTierId,
-        recipient: admin,
-      };
+  it("upgrades a Tree NFT to Bronze for the price delta", async function () {
+    const admin = provider.wallet.publicKey;
+    const treasuryPda = programPda("vigri-presale-treasury");
+    const treeTierId = 0;
+    const bronzeTierId = 1;
 
-      const adminMintAccounts = {
+    const treeBefore = await program.account.tierConfig.fetch(tierPda(treeTierId));
+    const bronzeBefore = await program.account.tierConfig.fetch(
+      tierPda(bronzeTierId)
+    );
+    const priceDelta = bronzeBefore.priceLamports.sub(treeBefore.priceLamports);
+    const movesPrice = (tier) =>
+      tier.pending.priceLamports && !tier.pending.priceLamports.eq(tier.priceLamports);
+    if (
+      movesPrice(treeBefore) ||
+      movesPrice(bronzeBefore) ||
+      (await provider.connection.getBalance(admin)) <
+        priceDelta.toNumber() + anchor.web3.LAMPORTS_PER_SOL
+    ) {
+      // A queued price change would move the delta, or the wallet cannot pay it
+      this.skip();
+    }
+
+    const { mint: oldMint } = await adminMintTo(treeTierId, admin, 1);
+    const newMint = anchor.web3.Keypair.generate();
+    const treasuryBefore = await provider.connection.getBalance(treasuryPda);
+    const treeMinted = await program.account.tierConfig.fetch(tierPda(treeTierId));
+
+    const upgradeTx = await program.methods
+      .upgradeNft({ designChoice: null, inviteProof: null })
+      .accounts({
+        owner: admin,
+        globalConfig: programPda("vigri-presale-config"),
+        fromTier: tierPda(treeTierId),
+        toTier: tierPda(bronzeTierId),
         admin,
-        globalConfig: globalConfigPda,
-        mint: mintKeypair.publicKey,
-        adminTokenAccount,
-        metadata: metadataPda,
+        treasury: treasuryPda,
+        oldMint,
+        oldNftRecord: nftRecordFor(oldMint),
+        oldClaimRecord: programPda("vigri-presale-claim", oldMint),
+        oldTokenAccount: ataFor(admin, oldMint),
+        oldMetadata: metadataFor(oldMint),
+        oldEdition: editionFor(oldMint),
+        ...(await collectionAccounts()),
+        mint: newMint.publicKey,
+        ownerTokenAccount: ataFor(admin, newMint.publicKey),
+        nftRecord: nftRecordFor(newMint.publicKey),
+        metadata: metadataFor(newMint.publicKey),
+        edition: editionFor(newMint.publicKey),
         tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
         tokenProgram: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         systemProgram: anchor.web3.SystemProgram.programId,
         rent: anchor.web3.SYSVAR_RENT_PUBKEY,
-      };
+        kycRecord: null,
+        instructions: null,
+        globalRateLimiter: null,
+        tierRateLimiter: null,
+      })
+      .signers([newMint])
+      .rpc({ commitment: "confirmed" });
 
-      const tx = await program.methods
-        .adminMint(adminMintArgs)
-        .accounts(adminMintAccounts)
-        .signers([mintKeypair])
-        .rpc();
+    console.log("upgrade_nft tx:", upgradeTx);
 
-      console.log("admin_mint Platinum tx:", tx);
+    // 1) Only the price delta reaches the treasury
+    const treasuryAfter = await provider.connection.getBalance(treasuryPda);
+    if (treasuryAfter - treasuryBefore !== priceDelta.toNumber()) {
+      throw new Error("treasury did not receive the price delta");
+    }
+
+    // 2) The Tree NFT counts as burned, the Bronze one takes the next serial
+    const treeAfter = await program.account.tierConfig.fetch(tierPda(treeTierId));
+    const bronzeAfter = await program.account.tierConfig.fetch(
+      tierPda(bronzeTierId)
+    );
+    const serial = bronzeBefore.supplyMinted + 1;
+    if (
+      treeAfter.supplyBurned !== treeMinted.supplyBurned + 1 ||
+      treeAfter.supplyMinted !== treeMinted.supplyMinted ||
+      bronzeAfter.supplyMinted !== serial
+    ) {
+      throw new Error("tier counters do not reflect the upgrade");
+    }
+
+    const record = await program.account.nftRecord.fetch(
+      nftRecordFor(newMint.publicKey)
+    );
+    if (record.tierId !== bronzeTierId || record.serial !== serial) {
+      throw new Error("upgraded NFT does not carry the next Bronze serial");
+    }
+
+    // 3) Event names both mints, both tiers and what was paid
+    const upgraded = findEvent(await eventsOf(upgradeTx), "NftUpgraded");
+    if (
+      !upgraded.owner.equals(admin) ||
+      !upgraded.oldMint.equals(oldMint) ||
+      !upgraded.newMint.equals(newMint.publicKey) ||
+      upgraded.fromTierId !== treeTierId ||
+      upgraded.toTierId !== bronzeTierId ||
+      upgraded.serial !== serial ||
+      !upgraded.pricePaid.eq(priceDelta)
+    ) {
+      throw new Error("NftUpgraded does not match the upgrade");
+    }
+  });
+
+  it("only allows holder-favourable schedule changes after claims", async function () {
+    const admin = provider.wallet.publicKey;
+    const bronzeTierId = 1;
+    const allocationPda = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vigri-presale-allocation"), Buffer.from([bronzeTierId])],
+      program.programId
+    )[0];
+
+    const allocation = await program.account.tierAllocation.fetchNullable(
+      allocationPda
+    );
+    if (!allocation || allocation.totalClaimed.isZero()) {
+      // Needs the claim made by the previous test
+      this.skip();
+    }
+
+    const current = {
+      amountPerNft: allocation.amountPerNft,
+      vestingStartTs: allocation.vestingStartTs,
+      cliffSeconds: allocation.cliffSeconds,
+      vestingSeconds: allocation.vestingSeconds,
     };
+    const setAllocation = (args: typeof current) =>
+      program.methods
+        .setTierAllocation(args)
+        .accounts({
+          admin,
+          globalConfig: programPda("vigri-presale-config"),
+          tier: tierPda(bronzeTierId),
+          tokenMint: allocation.tokenMint,
+          allocation: allocationPda,
+          vault: programPda("vigri-presale-vault", allocation.tokenMint),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
 
-    // First admin_mint for Platinum - must pass
-    await doAdminMintOnce();
+    // 1) Fewer tokens, a later start and a longer vesting are all rejected
+    await expectError(
+      () =>
+        setAllocation({
+          ...current,
+          amountPerNft: current.amountPerNft.subn(1),
+        }),
+      "AllocationScheduleLocked"
+    );
+    await expectError(
+      () =>
+        setAllocation({
+          ...current,
+          vestingStartTs: current.vestingStartTs.addn(3600),
+        }),
+      "AllocationScheduleLocked"
+    );
+    await expectError(
+      () =>
+        setAllocation({
+          ...current,
+          vestingSeconds: current.vestingSeconds.addn(3600),
+        }),
+      "AllocationScheduleLocked"
+    );
 
-    // The second admin_mint for Platinum should fall under the 5% limit
+    // 2) The unchanged schedule is still accepted
+    await setAllocation(current);
+
+    const after = await program.account.tierAllocation.fetch(allocationPda);
+    if (
+      !after.amountPerNft.eq(current.amountPerNft) ||
+      !after.vestingStartTs.eq(current.vestingStartTs)
+    ) {
+      throw new Error("unchanged schedule was not kept");
+    }
+  });
+
+  it("books staking points with the weight fixed at stake time", async function () {
+    const admin = provider.wallet.publicKey;
+    const globalConfigPda = programPda("vigri-presale-config");
+    const bronzeTierId = 1;
+
+    const config = await program.account.globalConfig.fetch(globalConfigPda);
+    const delay = config.configDelaySeconds.toNumber();
+    if (delay > 5) {
+      // Weight changes are timelocked, too long to wait for here
+      this.skip();
+    }
+
+    const setStakeWeight = async (weight: number) => {
+      await program.methods
+        .updateConfig(updateConfigArgs({ newStakeWeight: weight }))
+        .accounts({
+          authority: admin,
+          globalConfig: globalConfigPda,
+          tier: tierPda(bronzeTierId),
+        })
+        .rpc();
+      await new Promise((resolve) => setTimeout(resolve, (delay + 1) * 1000));
+      await program.methods
+        .applyPendingConfig()
+        .accounts({ globalConfig: globalConfigPda, tier: tierPda(bronzeTierId) })
+        .rpc();
+    };
+
+    const stakeWeight = 3;
+    await setStakeWeight(stakeWeight);
+
+    const { mint: nftMint } = await adminMintTo(bronzeTierId, admin);
+    const stakeAccounts = {
+      owner: admin,
+      tier: tierPda(bronzeTierId),
+      stakeAuthority: programPda("vigri-presale-stake-authority"),
+      nftMint,
+      nftTokenAccount: ataFor(admin, nftMint),
+      nftMetadata: metadataFor(nftMint),
+      nftEdition: editionFor(nftMint),
+      stakeRecord: programPda("vigri-presale-stake", nftMint),
+      stakerPoints: programPda("vigri-presale-points", admin),
+      tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+
+    // 1) Stake records the current tier weight
+    const stakeSig = await program.methods
+      .stakeNft()
+      .accounts({
+        ...stakeAccounts,
+        globalConfig: globalConfigPda,
+        nftRecord: nftRecordFor(nftMint),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc({ commitment: "confirmed" });
+
+    const staked = findEvent(await eventsOf(stakeSig), "NftStaked");
+    const record = await program.account.stakeRecord.fetch(
+      stakeAccounts.stakeRecord
+    );
+    if (staked.stakeWeight !== stakeWeight || record.stakeWeight !== stakeWeight) {
+      throw new Error("stake did not record the tier weight");
+    }
+
+    // 2) A later weight change does not touch the running stake
+    await setStakeWeight(stakeWeight + 5);
+
+    const unstakeSig = await program.methods
+      .unstakeNft()
+      .accounts(stakeAccounts)
+      .rpc({ commitment: "confirmed" });
+
+    const unstaked = findEvent(await eventsOf(unstakeSig), "NftUnstaked");
+    if (
+      unstaked.stakedSeconds.isZero() ||
+      !unstaked.pointsEarned.eq(unstaked.stakedSeconds.muln(stakeWeight))
+    ) {
+      throw new Error("points were not booked with the weight at stake time");
+    }
+  });
+
+  it("walks a redemption through its state machine", async () => {
+    const admin = provider.wallet.publicKey;
+    const globalConfigPda = programPda("vigri-presale-config");
+    const bronzeTierId = 1;
+
+    const { mint: nftMint } = await adminMintTo(bronzeTierId, admin);
+    const redemptionPda = programPda("vigri-presale-redemption", nftMint);
+    const nftAccounts = {
+      owner: admin,
+      redemptionAuthority: programPda("vigri-presale-redemption-authority"),
+      nftMint,
+      nftTokenAccount: ataFor(admin, nftMint),
+      nftMetadata: metadataFor(nftMint),
+      nftEdition: editionFor(nftMint),
+      redemption: redemptionPda,
+      tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+
+    const setStatus = (status) =>
+      program.methods
+        .setRedemptionStatus(status)
+        .accounts({
+          admin,
+          globalConfig: globalConfigPda,
+          redemption: redemptionPda,
+        })
+        .rpc();
+    const cancel = () =>
+      program.methods.cancelRedemption().accounts(nftAccounts).rpc();
+    const complete = async () =>
+      program.methods
+        .completeRedemption()
+        .accounts({
+          ...nftAccounts,
+          tier: tierPda(bronzeTierId),
+          collectionMetadata: (await collectionAccounts()).collectionMetadata,
+        })
+        .rpc({ commitment: "confirmed" });
+
+    await program.methods
+      .requestRedemption(Array.from(Buffer.alloc(32, 7)))
+      .accounts({
+        ...nftAccounts,
+        globalConfig: globalConfigPda,
+        tier: tierPda(bronzeTierId),
+        nftRecord: nftRecordFor(nftMint),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    // 1) Requested cannot skip to Shipped, nor be completed
+    await expectError(() => setStatus({ shipped: {} }), "InvalidRedemptionStatus");
+    await expectError(complete, "InvalidRedemptionStatus");
+
+    // 2) Once Approved the holder can no longer cancel
+    await setStatus({ approved: {} });
+    await expectError(cancel, "InvalidRedemptionStatus");
+    await expectError(() => setStatus({ requested: {} }), "InvalidRedemptionStatus");
+
+    // 3) Shipped: the admin must wait for the confirmation timeout
+    await setStatus({ shipped: {} });
+    const { owner: _owner, ...lockAccounts } = nftAccounts;
+    await expectError(
+      () =>
+        program.methods
+          .completeOverdueRedemption()
+          .accounts({
+            ...lockAccounts,
+            admin,
+            globalConfig: globalConfigPda,
+            tier: tierPda(bronzeTierId),
+          })
+          .rpc(),
+      "RedemptionNotOverdue"
+    );
+
+    // 4) The holder's confirmation burns the NFT
+    const tierBefore = await program.account.tierConfig.fetch(
+      tierPda(bronzeTierId)
+    );
+    await complete();
+
+    const redemption = await program.account.redemption.fetch(redemptionPda);
+    const tierAfter = await program.account.tierConfig.fetch(tierPda(bronzeTierId));
+    const mintInfo = await provider.connection.getParsedAccountInfo(nftMint);
+    if (
+      !("completed" in redemption.status) ||
+      tierAfter.supplyRedeemed !== tierBefore.supplyRedeemed + 1 ||
+      mintInfo.value?.data?.parsed?.info?.supply !== "0"
+    ) {
+      throw new Error("completed redemption did not burn the NFT");
+    }
+
+    // 5) Completed is final
+    await expectError(() => setStatus({ rejected: {} }), "InvalidRedemptionStatus");
+  });
+
+  it("binds a certificate only to the NFT carrying its serial", async () => {
+    const admin = provider.wallet.publicKey;
+    const globalConfigPda = programPda("vigri-presale-config");
+    const bronzeTierId = 1;
+
+    const { mint: mintA } = await adminMintTo(bronzeTierId, admin);
+    const { mint: mintB } = await adminMintTo(bronzeTierId, admin);
+    const serialA = (await program.account.nftRecord.fetch(nftRecordFor(mintA)))
+      .serial;
+    const serialB = (await program.account.nftRecord.fetch(nftRecordFor(mintB)))
+      .serial;
+
+    const certificateFor = (serial: number) => {
+      const serialLe = Buffer.alloc(2);
+      serialLe.writeUInt16LE(serial);
+      return anchor.web3.PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("vigri-presale-certificate"),
+          Buffer.from([bronzeTierId]),
+          serialLe,
+        ],
+        program.programId
+      )[0];
+    };
+    const setCertificate = (serial: number, nftMint: anchor.web3.PublicKey) =>
+      program.methods
+        .setCertificate(serial, Array.from(Buffer.alloc(32, serial % 256)))
+        .accounts({
+          admin,
+          globalConfig: globalConfigPda,
+          tier: tierPda(bronzeTierId),
+          nftMint,
+          nftRecord: nftRecordFor(nftMint),
+          nftMetadata: metadataFor(nftMint),
+          certificate: certificateFor(serial),
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+
+    // 1) Another NFT's serial, and a serial out of range, are rejected
+    await expectError(() => setCertificate(serialB, mintA), "InvalidCertificate");
+    await expectError(() => setCertificate(0, mintA), "InvalidCertificate");
+
+    // 2) The matching serial binds the certificate to the mint
+    await setCertificate(serialA, mintA);
+    const certificate = await program.account.certificate.fetch(
+      certificateFor(serialA)
+    );
+    if (!certificate.mint.equals(mintA) || certificate.serial !== serialA) {
+      throw new Error("certificate not bound to its NFT");
+    }
+
+    // 3) ... and the binding can't be moved to another NFT
+    await expectError(() => setCertificate(serialA, mintB), "InvalidCertificate");
+  });
+
+  it("guards buybacks with the pause, the budget and the price cap", async function () {
+    const admin = provider.wallet.publicKey;
+    const globalConfigPda = programPda("vigri-presale-config");
+    const buybackConfigPda = programPda("vigri-presale-buyback");
+    const buybackVault = programPda("vigri-presale-buyback-vault");
+    const treeTierId = 0;
+
+    const config = await program.account.globalConfig.fetch(globalConfigPda);
+    if (config.multisig.threshold > 0) {
+      // Budget and vault changes would need the multisig's signers
+      this.skip();
+    }
+
+    const setBuybackConfig = (args) =>
+      program.methods
+        .setBuybackConfig({
+          isPaused: null,
+          budgetLamports: null,
+          burnReturned: null,
+          ...args,
+        })
+        .accounts({
+          authority: admin,
+          globalConfig: globalConfigPda,
+          buybackConfig: buybackConfigPda,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    const setBuybackPrice = (priceLamports: anchor.BN) =>
+      program.methods
+        .setBuybackPrice(priceLamports)
+        .accounts({
+          admin,
+          globalConfig: globalConfigPda,
+          tier: tierPda(treeTierId),
+        })
+        .rpc();
+    const sellBack = async (nftMint: anchor.web3.PublicKey) =>
+      program.methods
+        .sellBackNft()
+        .accounts({
+          seller: admin,
+          globalConfig: globalConfigPda,
+          tier: tierPda(treeTierId),
+          buybackConfig: buybackConfigPda,
+          treasury: programPda("vigri-presale-treasury"),
+          nftMint,
+          nftRecord: nftRecordFor(nftMint),
+          nftTokenAccount: ataFor(admin, nftMint),
+          nftMetadata: metadataFor(nftMint),
+          nftEdition: editionFor(nftMint),
+          collectionMetadata: (await collectionAccounts()).collectionMetadata,
+          buybackVault,
+          vaultTokenAccount: ataFor(buybackVault, nftMint),
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+
+    // 1) The buyback price can't exceed the sale price
+    const tree = await program.account.tierConfig.fetch(tierPda(treeTierId));
+    await expectError(
+      () => setBuybackPrice(tree.priceLamports.addn(1)),
+      "InvalidBuybackPrice"
+    );
+
+    const price = new anchor.BN(1_000);
+    await setBuybackConfig({});
+    await setBuybackPrice(price);
+
+    // 2) Paused buyback rejects sales
+    const bought = await mintNftIx(treeTierId, admin);
+    await sendMints([bought]);
+    const heldMint = bought.mintKeypair.publicKey;
+    await setBuybackConfig({ isPaused: true });
+    await expectError(() => sellBack(heldMint), "BuybackPaused");
+
+    // 3) Budget for exactly one more NFT, held in the vault
+    const buyback = await program.account.buybackConfig.fetch(buybackConfigPda);
+    await setBuybackConfig({
+      isPaused: false,
+      budgetLamports: buyback.spentLamports.add(price),
+      burnReturned: false,
+    });
+
+    // ... but a granted NFT was never paid for, so it is not bought back
+    const { mint: grantedMint } = await adminMintTo(treeTierId, admin, 1);
+    await expectError(() => sellBack(grantedMint), "BuybackNotPaid");
+
+    await sellBack(heldMint);
+
+    const vaultBalance = await provider.connection.getTokenAccountBalance(
+      ataFor(buybackVault, heldMint)
+    );
+    if (vaultBalance.value.amount !== "1") {
+      throw new Error("bought-back NFT is not in the vault");
+    }
+
+    // 4) The admin can hand the held NFT out again
+    await program.methods
+      .releaseBuybackNft()
+      .accounts({
+        admin,
+        globalConfig: globalConfigPda,
+        treasury: programPda("vigri-presale-treasury"),
+        nftMint: heldMint,
+        buybackVault,
+        vaultTokenAccount: ataFor(buybackVault, heldMint),
+        destinationTokenAccount: ataFor(admin, heldMint),
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    const released = await provider.connection.getTokenAccountBalance(
+      ataFor(admin, heldMint)
+    );
+    if (released.value.amount !== "1") {
+      throw new Error("held NFT was not released");
+    }
+
+    // 5) ... but the budget is now spent
+    await expectError(() => sellBack(heldMint), "BuybackBudgetExhausted");
+
+    // Close the offer again
+    await setBuybackPrice(new anchor.BN(0));
+  });
+
+  it("enforces the admin mint quota for Platinum", async () => {
+    const admin = provider.wallet.publicKey;
+
+    const [globalConfigPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vigri-presale-config")],
+      program.programId
+    );
+
+    // Platinum tier (id = 4, supply_total = 20, admin_quota = 1)
+    const platinumTierId = 4;
+
+    const TOKEN_PROGRAM_ID = new anchor.web3.PublicKey(
+      "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+    );
+    const ASSOCIATED_TOKEN_PROGRAM_ID = new anchor.web3.PublicKey(
+      "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
+    );
+    const TOKEN_METADATA_PROGRAM_ID = new anchor.web3.PublicKey(
+      "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s"
+    );
+
+    const doAdminMintOnce = async () => {
+      const mintKeypair = anchor.web3.Keypair.generate();
+
+      const [recipientTokenAccount] =
+        anchor.web3.PublicKey.findProgramAddressSync(
+          [
+            admin.toBuffer(),
+            TOKEN_PROGRAM_ID.toBuffer(),
+            mintKeypair.publicKey.toBuffer(),
+          ],
+          ASSOCIATED_TOKEN_PROGRAM_ID
+        );
+
+      const [metadataPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("metadata"),
+          TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+          mintKeypair.publicKey.toBuffer(),
+        ],
+        TOKEN_METADATA_PROGRAM_ID
+      );
+
+      const adminMintArgs = {
+        designChoice: null,
+      };
+
+      const adminMintAccounts = {
+        authority: admin,
+        admin,
+        globalConfig: globalConfigPda,
+        tier: tierPda(platinumTierId),
+        mint: mintKeypair.publicKey,
+        recipient: admin,
+        recipientTokenAccount,
+        nftRecord: nftRecordFor(mintKeypair.publicKey),
+        metadata: metadataPda,
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      };
+
+      const tx = await program.methods
+        .adminMint(adminMintArgs)
+        .accounts(adminMintAccounts)
+        .signers([mintKeypair])
+        .rpc();
+
+      console.log("admin_mint Platinum tx:", tx);
+    };
+
+    // First admin_mint for Platinum - must pass
+    await doAdminMintOnce();
+
+    // The second admin_mint for Platinum must hit the quota
     try {
       await doAdminMintOnce();
       throw new Error(
-        "Second admin_mint for Platinum unexpectedly succeeded (limit 5% not enforced)"
+        "Second admin_mint for Platinum unexpectedly succeeded (admin quota not enforced)"
       );
     } catch (err) {
+      if (!String(err).includes("AdminQuotaExceeded")) {
+        throw err;
+      }
       console.log(
-        "Second admin_mint for Platinum failed as expected (5% limit enforced)"
+        "Second admin_mint for Platinum failed as expected (AdminQuotaExceeded)"
       );
     }
   });
+
+  it("rejects tier labels that overflow the Metaplex name", async () => {
+    const admin = provider.wallet.publicKey;
+
+    const [globalConfigPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vigri-presale-config")],
+      program.programId
+    );
+
+    // "VIGRI " + 15 chars + " NFT #000001" = 33 > 32
+    const unusedTierId = 200;
+    await expectError(
+      () =>
+        program.methods
+          .createTier({
+            tierId: unusedTierId,
+            supplyTotal: 10,
+            priceLamports: new anchor.BN(0),
+            kycRequired: false,
+            inviteOnly: false,
+            transferable: true,
+            designVariants: 0,
+            label: "Fifteen-Letters",
+            uriBase: "https://vigri.ee/metadata/nft/test",
+            adminQuota: 0,
+            supplyMax: 10,
+            stakeWeight: 0,
+          })
+          .accounts({
+            admin,
+            globalConfig: globalConfigPda,
+            tier: tierPda(unusedTierId),
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .rpc(),
+      "InvalidTierConfig"
+    );
+  });
+
+  it("airdrops tier 0 NFTs to several recipients in one transaction", async () => {
+    const admin = provider.wallet.publicKey;
+
+    const [globalConfigPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vigri-presale-config")],
+      program.programId
+    );
+
+    const TOKEN_PROGRAM_ID = new anchor.web3.PublicKey(
+      "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+    );
+    const ASSOCIATED_TOKEN_PROGRAM_ID = new anchor.web3.PublicKey(
+      "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
+    );
+    const TOKEN_METADATA_PROGRAM_ID = new anchor.web3.PublicKey(
+      "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s"
+    );
+
+    const tierBefore = await program.account.tierConfig.fetch(tierPda(0));
+
+    // Two recipients fit into one transaction (airdrop_recipients_per_tx in
+    // Rust, asserted by its unit test)
+    const recipients = [
+      anchor.web3.Keypair.generate().publicKey,
+      anchor.web3.Keypair.generate().publicKey,
+    ];
+    const mintKeypairs = recipients.map(() => anchor.web3.Keypair.generate());
+
+    // Per recipient: recipient, mint, ATA, metadata, edition, NFT record
+    const remainingAccounts = recipients.flatMap((recipient, i) => {
+      const mint = mintKeypairs[i].publicKey;
+
+      const [recipientTokenAccount] =
+        anchor.web3.PublicKey.findProgramAddressSync(
+          [recipient.toBuffer(), TOKEN_PROGRAM_ID.toBuffer(), mint.toBuffer()],
+          ASSOCIATED_TOKEN_PROGRAM_ID
+        );
+
+      const [metadataPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("metadata"),
+          TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+          mint.toBuffer(),
+        ],
+        TOKEN_METADATA_PROGRAM_ID
+      );
+
+      const [editionPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("metadata"),
+          TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+          mint.toBuffer(),
+          Buffer.from("edition"),
+        ],
+        TOKEN_METADATA_PROGRAM_ID
+      );
+
+      return [
+        { pubkey: recipient, isSigner: false, isWritable: false },
+        { pubkey: mint, isSigner: true, isWritable: true },
+        { pubkey: recipientTokenAccount, isSigner: false, isWritable: true },
+        { pubkey: metadataPda, isSigner: false, isWritable: true },
+        { pubkey: editionPda, isSigner: false, isWritable: true },
+        { pubkey: nftRecordFor(mint), isSigner: false, isWritable: true },
+      ];
+    });
+
+    const airdropTx = await program.methods
+      .adminAirdrop({ designChoice: 1 })
+      .accounts({
+        authority: admin,
+        admin,
+        globalConfig: globalConfigPda,
+        tier: tierPda(0),
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .remainingAccounts(remainingAccounts)
+      .preInstructions([
+        anchor.web3.ComputeBudgetProgram.setComputeUnitLimit({
+          units: 1_400_000,
+        }),
+      ])
+      .signers(mintKeypairs)
+      .rpc();
+
+    console.log("admin_airdrop tx:", airdropTx);
+
+    const tierAfter = await program.account.tierConfig.fetch(tierPda(0));
+    if (
+      tierAfter.supplyMinted !== tierBefore.supplyMinted + recipients.length ||
+      tierAfter.adminMinted !== tierBefore.adminMinted + recipients.length
+    ) {
+      throw new Error("admin_airdrop did not count every recipient");
+    }
+  });
+
+  it("rejects supply changes outside minted..supply_max", async () => {
+    const admin = provider.wallet.publicKey;
+
+    const [globalConfigPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vigri-presale-config")],
+      program.programId
+    );
+
+    // Platinum already minted at least one NFT above
+    const platinum = await program.account.tierConfig.fetch(tierPda(4));
+
+    const trySupply = async (newSupplyTotal: number) => {
+      try {
+        await program.methods
+          .updateConfig(updateConfigArgs({ newSupplyTotal }))
+          .accounts({
+            authority: admin,
+            globalConfig: globalConfigPda,
+            tier: tierPda(4),
+          })
+          .rpc();
+      } catch (err) {
+        if (String(err).includes("InvalidSupply")) {
+          return;
+        }
+        throw err;
+      }
+      throw new Error(`supply_total ${newSupplyTotal} unexpectedly accepted`);
+    };
+
+    // Below what is already minted
+    await trySupply(platinum.supplyMinted - 1);
+    // Above the hard cap committed at create_tier
+    await trySupply(platinum.supplyMax + 1);
+
+    // Below Gold's admin quota (only meaningful while fewer were minted)
+    const gold = await program.account.tierConfig.fetch(tierPda(3));
+    if (gold.adminQuota > 0 && gold.supplyMinted < gold.adminQuota) {
+      await expectError(
+        () =>
+          program.methods
+            .updateConfig(
+              updateConfigArgs({ newSupplyTotal: gold.adminQuota - 1 })
+            )
+            .accounts({
+              authority: admin,
+              globalConfig: globalConfigPda,
+              tier: tierPda(3),
+            })
+            .rpc(),
+        "InvalidTierConfig"
+      );
+    }
+  });
+
+  it("emits typed events for config changes", async () => {
+    const admin = provider.wallet.publicKey;
+
+    const [globalConfigPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vigri-presale-config")],
+      program.programId
+    );
+
+    const updateConfig = (overrides, tier = null) =>
+      program.methods
+        .updateConfig(updateConfigArgs(overrides))
+        .accounts({ authority: admin, globalConfig: globalConfigPda, tier })
+        .rpc({ commitment: "confirmed" });
+
+    // 1) Global pause switch
+    const pauseTx = await updateConfig({ isSalesPaused: true });
+    const resumeTx = await updateConfig({ isSalesPaused: false });
+
+    const paused = findEvent(await eventsOf(pauseTx), "SalesPaused");
+    const resumed = findEvent(await eventsOf(resumeTx), "SalesResumed");
+    if (!paused.authority.equals(admin) || !resumed.authority.equals(admin)) {
+      throw new Error("pause events do not name the authority");
+    }
+
+    // 2) Immediate tier flag (anti-bot, re-set to its current value)
+    const bronze = await program.account.tierConfig.fetch(tierPda(1));
+    const flagsTx = await updateConfig(
+      { newAntiBotMaxMints: bronze.antiBotMaxMints },
+      tierPda(1)
+    );
+
+    const flags = findEvent(await eventsOf(flagsTx), "TierFlagsChanged");
+    if (
+      flags.tierId !== 1 ||
+      flags.antiBotMaxMints !== bronze.antiBotMaxMints ||
+      flags.transferable !== bronze.transferable
+    ) {
+      throw new Error("TierFlagsChanged does not match the tier");
+    }
+  });
+
+  it("pauses and resumes a single tier", async () => {
+    const admin = provider.wallet.publicKey;
+
+    const [globalConfigPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vigri-presale-config")],
+      program.programId
+    );
+
+    const setSilverPaused = async (tierPaused: boolean) => {
+      const tx = await program.methods
+        .updateConfig(updateConfigArgs({ tierPaused }))
+        .accounts({
+          authority: admin,
+          globalConfig: globalConfigPda,
+          tier: tierPda(2),
+        })
+        .rpc();
+
+      console.log(`update_config tier 2 paused=${tierPaused} tx:`, tx);
+    };
+
+    await setSilverPaused(true);
+
+    const silver = await program.account.tierConfig.fetch(tierPda(2));
+    const bronze = await program.account.tierConfig.fetch(tierPda(1));
+    if (!silver.isPaused || bronze.isPaused) {
+      throw new Error("Tier pause did not stay scoped to Silver");
+    }
+
+    const globalConfig = await program.account.globalConfig.fetch(
+      globalConfigPda
+    );
+    if (globalConfig.isSalesPaused) {
+      throw new Error("Tier pause must not touch the global switch");
+    }
+
+    await setSilverPaused(false);
+  });
+
+  it("configures and clears a per-tier rate limit", async () => {
+    const admin = provider.wallet.publicKey;
+
+    const [globalConfigPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vigri-presale-config")],
+      program.programId
+    );
+
+    // Bronze throttle PDA (must match RATE_LIMIT_SEED in Rust)
+    const bronzeTierId = 1;
+    const [rateLimiterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vigri-presale-rate-limit"), Buffer.from([bronzeTierId])],
+      program.programId
+    );
+
+    const setBronzeRateLimit = async (maxMints: number, windowSlots: number) => {
+      const tx = await program.methods
+        .setRateLimit(bronzeTierId, maxMints, new anchor.BN(windowSlots))
+        .accounts({
+          admin,
+          globalConfig: globalConfigPda,
+          tier: tierPda(bronzeTierId),
+          rateLimiter: rateLimiterPda,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+
+      console.log(`set_rate_limit Bronze ${maxMints}/${windowSlots} tx:`, tx);
+    };
+
+    // 5 mints per ~10 seconds (25 slots)
+    await setBronzeRateLimit(5, 25);
+
+    const limiter = await program.account.rateLimiter.fetch(rateLimiterPda);
+    const bronze = await program.account.tierConfig.fetch(tierPda(bronzeTierId));
+    if (limiter.maxMints !== 5 || !bronze.rateLimited) {
+      throw new Error("Bronze rate limit was not stored");
+    }
+
+    // max_mints = 0 switches the throttle off again
+    await setBronzeRateLimit(0, 0);
+
+    const bronzeAfter = await program.account.tierConfig.fetch(
+      tierPda(bronzeTierId)
+    );
+    if (bronzeAfter.rateLimited) {
+      throw new Error("Bronze rate limit was not cleared");
+    }
+  });
+
+  it("rejects extra mints and foreign instructions in anti-bot mode", async () => {
+    const admin = provider.wallet.publicKey;
+    const tierId = 0;
+
+    const setAntiBotMaxMints = (maxMints: number) =>
+      program.methods
+        .updateConfig(updateConfigArgs({ newAntiBotMaxMints: maxMints }))
+        .accounts({
+          authority: admin,
+          globalConfig: programPda("vigri-presale-config"),
+          tier: tierPda(tierId),
+        })
+        .rpc();
+
+    const guardedMint = () =>
+      mintNftIx(tierId, admin, admin, {
+        instructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+      });
+
+    const tree = await program.account.tierConfig.fetch(tierPda(tierId));
+    await setAntiBotMaxMints(1);
+
+    try {
+      // 1) max_mints + 1 mint_nft instructions in one transaction
+      await expectError(
+        async () => sendMints([await guardedMint(), await guardedMint()]),
+        "AntiBotRejected"
+      );
+
+      // 2) A SystemProgram transfer next to the mint
+      await expectError(
+        async () =>
+          sendMints(
+            [await guardedMint()],
+            [
+              anchor.web3.SystemProgram.transfer({
+                fromPubkey: admin,
+                toPubkey: anchor.web3.Keypair.generate().publicKey,
+                lamports: 1_000_000,
+              }),
+            ]
+          ),
+        "AntiBotRejected"
+      );
+    } finally {
+      await setAntiBotMaxMints(tree.antiBotMaxMints);
+    }
+
+    const treeAfter = await program.account.tierConfig.fetch(tierPda(tierId));
+    if (treeAfter.supplyMinted !== tree.supplyMinted) {
+      throw new Error("a rejected transaction minted an NFT");
+    }
+  });
+
+  it("timelocks the voucher signer and redeems only valid vouchers", async function () {
+    const admin = provider.wallet.publicKey;
+
+    const [globalConfigPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vigri-presale-config")],
+      program.programId
+    );
+
+    const config = await program.account.globalConfig.fetch(globalConfigPda);
+    if (config.multisig.threshold > 0) {
+      // New signers would need a SetVoucherSigner proposal
+      this.skip();
+    }
+
+    // Backend key signing fiat purchase vouchers
+    const backend = anchor.web3.Keypair.generate();
+
+    const setVoucherSigner = async (signer: anchor.web3.PublicKey) => {
+      const tx = await program.methods
+        .setVoucherSigner(signer)
+        .accounts({
+          admin,
+          globalConfig: globalConfigPda,
+        })
+        .rpc();
+
+      console.log("set_voucher_signer tx:", tx);
+    };
+    const clearVoucherSigner = async () => {
+      // default() switches redeem_voucher off at once and drops a queued key
+      await setVoucherSigner(anchor.web3.PublicKey.default);
+
+      const cleared = await program.account.globalConfig.fetch(globalConfigPda);
+      if (
+        !cleared.voucherSigner.equals(anchor.web3.PublicKey.default) ||
+        cleared.pendingVoucherSigner !== null
+      ) {
+        throw new Error("Voucher signer was not cleared");
+      }
+    };
+
+    // 1) A new signer is only queued
+    await setVoucherSigner(backend.publicKey);
+
+    const queued = await program.account.globalConfig.fetch(globalConfigPda);
+    if (
+      !queued.pendingVoucherSigner?.equals(backend.publicKey) ||
+      queued.voucherSigner.equals(backend.publicKey)
+    ) {
+      throw new Error("Voucher signer was not timelocked");
+    }
+
+    const delay = queued.configDelaySeconds.toNumber();
+    if (delay > 5) {
+      // Too long to wait for here
+      await clearVoucherSigner();
+      this.skip();
+    }
+
+    await new Promise((resolve) => setTimeout(resolve, (delay + 1) * 1000));
+    await program.methods
+      .applyPendingConfig()
+      .accounts({ globalConfig: globalConfigPda, tier: null })
+      .rpc();
+
+    const applied = await program.account.globalConfig.fetch(globalConfigPda);
+    if (!applied.voucherSigner.equals(backend.publicKey)) {
+      throw new Error("Voucher signer was not applied");
+    }
+
+    // 2) Vouchers for Bronze, signed over domain + program id + borsh(voucher)
+    const bronzeTierId = 1;
+    const now = Math.floor(Date.now() / 1000);
+    const voucherFor = (overrides = {}) => ({
+      tierId: bronzeTierId,
+      recipient: admin,
+      designChoice: null,
+      expiresAt: new anchor.BN(now + 600),
+      nonce: new anchor.BN(Date.now()).muln(1000).addn(Math.floor(Math.random() * 1000)),
+      ...overrides,
+    });
+    const voucherMessage = (voucher) => {
+      const fields = Buffer.alloc(1 + 32 + 2 + 8 + 8);
+      let offset = fields.writeUInt8(voucher.tierId, 0);
+      offset += voucher.recipient.toBuffer().copy(fields, offset);
+      if (voucher.designChoice === null) {
+        offset = fields.writeUInt8(0, offset);
+      } else {
+        offset = fields.writeUInt8(1, offset);
+        offset = fields.writeUInt8(voucher.designChoice, offset);
+      }
+      offset = fields.writeBigInt64LE(BigInt(voucher.expiresAt.toString()), offset);
+      offset = fields.writeBigUInt64LE(BigInt(voucher.nonce.toString()), offset);
+
+      return Buffer.concat([
+        Buffer.from("vigri-presale-voucher-v1"),
+        program.programId.toBuffer(),
+        fields.subarray(0, offset),
+      ]);
+    };
+
+    const redeem = async (
+      voucher,
+      signer: anchor.web3.Keypair = backend,
+      signedVoucher = voucher
+    ) => {
+      const mint = anchor.web3.Keypair.generate();
+      const voucherRecord = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("vigri-presale-voucher"), voucher.nonce.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      )[0];
+
+      await program.methods
+        .redeemVoucher(voucher)
+        .accounts({
+          payer: admin,
+          globalConfig: globalConfigPda,
+          tier: tierPda(voucher.tierId),
+          voucherRecord,
+          admin,
+          ...(await collectionAccounts()),
+          mint: mint.publicKey,
+          recipient: voucher.recipient,
+          recipientTokenAccount: ataFor(voucher.recipient, mint.publicKey),
+          nftRecord: nftRecordFor(mint.publicKey),
+          metadata: metadataFor(mint.publicKey),
+          edition: editionFor(mint.publicKey),
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+          instructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .preInstructions([
+          anchor.web3.Ed25519Program.createInstructionWithPrivateKey({
+            privateKey: signer.secretKey,
+            message: voucherMessage(signedVoucher),
+          }),
+        ])
+        .signers([mint])
+        .rpc();
+
+      return mint.publicKey;
+    };
+
+    // 3) A valid voucher mints to its recipient ...
+    const voucher = voucherFor();
+    const minted = await redeem(voucher);
+    const balance = await provider.connection.getTokenAccountBalance(
+      ataFor(admin, minted)
+    );
+    if (balance.value.amount !== "1") {
+      throw new Error("voucher NFT was not minted");
+    }
+
+    // ... exactly once
+    await expectError(() => redeem(voucher), "already in use");
+
+    // 4) Wrong signer, tampered fields and expired vouchers are rejected
+    await expectError(
+      () => redeem(voucherFor(), anchor.web3.Keypair.generate()),
+      "InvalidVoucher"
+    );
+
+    const signed = voucherFor();
+    await expectError(
+      () => redeem({ ...signed, expiresAt: signed.expiresAt.addn(3600) }, backend, signed),
+      "InvalidVoucher"
+    );
+
+    await expectError(
+      () => redeem(voucherFor({ expiresAt: new anchor.BN(now - 60) })),
+      "VoucherExpired"
+    );
+
+    await clearVoucherSigner();
+  });
+
+  it("writes and revokes a KYC registry entry", async () => {
+    const admin = provider.wallet.publicKey;
+
+    const [globalConfigPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vigri-presale-config")],
+      program.programId
+    );
+
+    // Registry PDA of a verified buyer (must match KYC_SEED in Rust)
+    const buyer = anchor.web3.Keypair.generate().publicKey;
+    const [kycRecordPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vigri-presale-kyc"), buyer.toBuffer()],
+      program.programId
+    );
+
+    // Admin holds every role, including the KYC manager
+    const expiresAt = Math.floor(Date.now() / 1000) + 365 * 24 * 60 * 60;
+    const setTx = await program.methods
+      .setKycRecord(buyer, {
+        level: { enhanced: {} },
+        expiresAt: new anchor.BN(expiresAt),
+        countryCode: Array.from(Buffer.from("EE")),
+      })
+      .accounts({
+        authority: admin,
+        globalConfig: globalConfigPda,
+        kycRecord: kycRecordPda,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    console.log("set_kyc_record tx:", setTx);
+
+    const record = await program.account.kycRecord.fetch(kycRecordPda);
+    if (
+      !record.wallet.equals(buyer) ||
+      record.expiresAt.toNumber() !== expiresAt ||
+      Buffer.from(record.countryCode).toString() !== "EE"
+    ) {
+      throw new Error("KYC entry was not stored");
+    }
+
+    const revokeTx = await program.methods
+      .revokeKycRecord(buyer)
+      .accounts({
+        authority: admin,
+        globalConfig: globalConfigPda,
+        kycRecord: kycRecordPda,
+      })
+      .rpc();
+
+    console.log("revoke_kyc_record tx:", revokeTx);
+
+    const closed = await program.account.kycRecord.fetchNullable(kycRecordPda);
+    if (closed !== null) {
+      throw new Error("KYC entry was not revoked");
+    }
+  });
+
+  it("routes tier prices through multisig proposals", async function () {
+    const admin = provider.wallet.publicKey;
+
+    const [globalConfigPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vigri-presale-config")],
+      program.programId
+    );
+
+    const config = await program.account.globalConfig.fetch(globalConfigPda);
+    if (config.multisig.threshold > 0) {
+      // Another run left a multisig behind; its signers are unknown here
+      this.skip();
+    }
+
+    // Buyback config PDA (created with nothing changed, if still missing)
+    const buybackConfigPda = programPda("vigri-presale-buyback");
+    await program.methods
+      .setBuybackConfig({ isPaused: null, budgetLamports: null, burnReturned: null })
+      .accounts({
+        authority: admin,
+        globalConfig: globalConfigPda,
+        buybackConfig: buybackConfigPda,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    // Fee payer stays the provider wallet, so the signers need no SOL
+    const signerA = anchor.web3.Keypair.generate();
+    const signerB = anchor.web3.Keypair.generate();
+
+    // 2-of-3: admin + two fresh keys
+    await program.methods
+      .setMultisig([admin, signerA.publicKey, signerB.publicKey], 2)
+      .accounts({ admin, globalConfig: globalConfigPda })
+      .rpc();
+
+    const bronzeTierId = 1;
+    const bronze = await program.account.tierConfig.fetch(tierPda(bronzeTierId));
+
+    // 1) The price manager path is closed while the multisig is active
+    await expectError(
+      () =>
+        program.methods
+          .updateConfig(
+            updateConfigArgs({ newPriceLamports: bronze.priceLamports })
+          )
+          .accounts({
+            authority: admin,
+            globalConfig: globalConfigPda,
+            tier: tierPda(bronzeTierId),
+          })
+          .rpc(),
+      "MultisigRequired"
+    );
+
+    // Proposal PDA (must match PROPOSAL_SEED in Rust)
+    const proposalPda = (id: anchor.BN) =>
+      anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("vigri-presale-proposal"), id.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      )[0];
+
+    const propose = async (action) => {
+      const { multisig } = await program.account.globalConfig.fetch(
+        globalConfigPda
+      );
+      const proposal = proposalPda(multisig.proposalCount);
+
+      await program.methods
+        .proposeAction(action)
+        .accounts({
+          proposer: admin,
+          globalConfig: globalConfigPda,
+          proposal,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+
+      return proposal;
+    };
+
+    const approve = (proposal, signer: anchor.web3.Keypair) =>
+      program.methods
+        .approveAction()
+        .accounts({
+          signer: signer.publicKey,
+          globalConfig: globalConfigPda,
+          proposal,
+        })
+        .signers([signer])
+        .rpc();
+
+    const execute = (proposal, tier = null, buybackConfig = null) =>
+      program.methods
+        .executeAction()
+        .accounts({
+          executor: admin,
+          globalConfig: globalConfigPda,
+          proposal,
+          tier,
+          newCollectionMint: null,
+          treasury: null,
+          destination: null,
+          buybackConfig,
+          buybackVault: null,
+          vaultTokenAccount: null,
+          destinationTokenAccount: null,
+          tokenProgram: null,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+
+    // Queues a no-op transferable change through the admin path
+    const queueTransferable = () =>
+      program.methods
+        .updateConfig(updateConfigArgs({ newTransferable: bronze.transferable }))
+        .accounts({
+          authority: admin,
+          globalConfig: globalConfigPda,
+          tier: tierPda(bronzeTierId),
+        })
+        .rpc();
+
+    // 2) Re-queue the current Bronze price, so applying it later is a no-op
+    await queueTransferable();
+    const priceProposal = await propose({
+      setTierPrice: { tierId: bronzeTierId, priceLamports: bronze.priceLamports },
+    });
+
+    // Only the proposer approved so far: 1 of 2
+    await expectError(
+      () => execute(priceProposal, tierPda(bronzeTierId)),
+      "ThresholdNotMet"
+    );
+
+    await approve(priceProposal, signerA);
+    await expectError(() => approve(priceProposal, signerA), "AlreadyApproved");
+
+    await execute(priceProposal, tierPda(bronzeTierId));
+    await expectError(
+      () => execute(priceProposal, tierPda(bronzeTierId)),
+      "ProposalAlreadyExecuted"
+    );
+
+    const bronzeAfter = await program.account.tierConfig.fetch(
+      tierPda(bronzeTierId)
+    );
+    if (!bronzeAfter.pending.priceLamports?.eq(bronze.priceLamports)) {
+      throw new Error("approved price change was not queued");
+    }
+    if (bronzeAfter.pending.transferable !== bronze.transferable) {
+      throw new Error("approved price change dropped the queued flag change");
+    }
+
+    // A later single-key flag update keeps the approved price
+    await queueTransferable();
+
+    const bronzeMerged = await program.account.tierConfig.fetch(
+      tierPda(bronzeTierId)
+    );
+    if (!bronzeMerged.pending.priceLamports?.eq(bronze.priceLamports)) {
+      throw new Error("flag update discarded the approved price change");
+    }
+
+    // 3) Buyback budget changes are proposals too
+    const buyback = await program.account.buybackConfig.fetch(buybackConfigPda);
+    const newBudget = buyback.budgetLamports.addn(1);
+    await expectError(
+      () =>
+        program.methods
+          .setBuybackConfig({
+            isPaused: null,
+            budgetLamports: newBudget,
+            burnReturned: null,
+          })
+          .accounts({
+            authority: admin,
+            globalConfig: globalConfigPda,
+            buybackConfig: buybackConfigPda,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .rpc(),
+      "MultisigRequired"
+    );
+
+    const budgetProposal = await propose({
+      setBuybackBudget: { budgetLamports: newBudget },
+    });
+    await approve(budgetProposal, signerA);
+    await execute(budgetProposal, null, buybackConfigPda);
+
+    const buybackAfter = await program.account.buybackConfig.fetch(
+      buybackConfigPda
+    );
+    if (!buybackAfter.budgetLamports.eq(newBudget)) {
+      throw new Error("approved buyback budget was not applied");
+    }
+
+    // 4) A new voucher signer needs a proposal, and still waits for the timelock
+    const voucherSigner = anchor.web3.Keypair.generate().publicKey;
+    await expectError(
+      () =>
+        program.methods
+          .setVoucherSigner(voucherSigner)
+          .accounts({ admin, globalConfig: globalConfigPda })
+          .rpc(),
+      "MultisigRequired"
+    );
+
+    const voucherProposal = await propose({ setVoucherSigner: { voucherSigner } });
+    await approve(voucherProposal, signerB);
+    await execute(voucherProposal);
+
+    const configQueued = await program.account.globalConfig.fetch(globalConfigPda);
+    if (!configQueued.pendingVoucherSigner?.equals(voucherSigner)) {
+      throw new Error("approved voucher signer was not queued");
+    }
+
+    // Switching vouchers off stays immediate (and drops the queued key)
+    await program.methods
+      .setVoucherSigner(anchor.web3.PublicKey.default)
+      .accounts({ admin, globalConfig: globalConfigPda })
+      .rpc();
+
+    // 5) Switch the multisig off again (no signers, threshold 0)
+    const disableProposal = await propose({
+      setMultisig: {
+        signers: Array(5).fill(anchor.web3.PublicKey.default),
+        signerCount: 0,
+        threshold: 0,
+      },
+    });
+    await approve(disableProposal, signerB);
+    await execute(disableProposal);
+
+    const configAfter = await program.account.globalConfig.fetch(
+      globalConfigPda
+    );
+    if (configAfter.multisig.threshold !== 0) {
+      throw new Error("multisig was not disabled");
+    }
+  });
+
+  it("applies longer config delays at once and timelocks shorter ones", async function () {
+    const admin = provider.wallet.publicKey;
+
+    const [globalConfigPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vigri-presale-config")],
+      program.programId
+    );
+
+    const config = await program.account.globalConfig.fetch(globalConfigPda);
+    const delay = config.configDelaySeconds.toNumber();
+    if (delay === 0) {
+      // Nothing shorter to queue
+      this.skip();
+    }
+
+    const setDelay = (delaySeconds: number) =>
+      program.methods
+        .setConfigDelay(new anchor.BN(delaySeconds))
+        .accounts({ admin, globalConfig: globalConfigPda })
+        .rpc();
+
+    const applyPending = () =>
+      program.methods
+        .applyPendingConfig()
+        .accounts({ globalConfig: globalConfigPda, tier: null })
+        .rpc();
+
+    // 1) A shorter delay is only queued
+    await setDelay(delay - 1);
+
+    const queued = await program.account.globalConfig.fetch(globalConfigPda);
+    if (
+      queued.configDelaySeconds.toNumber() !== delay ||
+      queued.pendingConfigDelay?.toNumber() !== delay - 1
+    ) {
+      throw new Error("shorter delay was applied immediately");
+    }
+
+    // 2) Applying it before the current delay has passed fails
+    await expectError(applyPending, "NoPendingChange");
+
+    if (delay > 5) {
+      // Too long to wait for here: re-setting the current delay drops the queued one
+      await setDelay(delay);
+
+      const restored = await program.account.globalConfig.fetch(globalConfigPda);
+      if (restored.pendingConfigDelay !== null) {
+        throw new Error("queued delay decrease was not dropped");
+      }
+      return;
+    }
+
+    // 3) Once due, the shorter delay applies
+    await new Promise((resolve) => setTimeout(resolve, (delay + 1) * 1000));
+    await applyPending();
+
+    const applied = await program.account.globalConfig.fetch(globalConfigPda);
+    if (
+      applied.configDelaySeconds.toNumber() !== delay - 1 ||
+      applied.pendingConfigDelay !== null
+    ) {
+      throw new Error("due delay decrease was not applied");
+    }
+
+    // 4) Going back up is immediate
+    await setDelay(delay);
+
+    const increased = await program.account.globalConfig.fetch(globalConfigPda);
+    if (increased.configDelaySeconds.toNumber() !== delay) {
+      throw new Error("delay increase was not applied immediately");
+    }
+  });
+
+  it("transfers admin in two steps and back", async () => {
+    const admin = provider.wallet.publicKey;
+
+    const [globalConfigPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vigri-presale-config")],
+      program.programId
+    );
+
+    // Fee payer stays the provider wallet, so the new key needs no SOL
+    const newAdmin = anchor.web3.Keypair.generate();
+
+    // 1) Propose + cancel
+    await program.methods
+      .proposeAdmin(newAdmin.publicKey)
+      .accounts({ admin, globalConfig: globalConfigPda })
+      .rpc();
+
+    await program.methods
+      .cancelAdminTransfer()
+      .accounts({ admin, globalConfig: globalConfigPda })
+      .rpc();
+
+    await expectError(
+      () =>
+        program.methods
+          .acceptAdmin()
+          .accounts({
+            newAdmin: newAdmin.publicKey,
+            globalConfig: globalConfigPda,
+          })
+          .signers([newAdmin])
+          .rpc(),
+      "NoPendingAdmin"
+    );
+
+    // 2) Propose + accept, then hand the role back
+    await program.methods
+      .proposeAdmin(newAdmin.publicKey)
+      .accounts({ admin, globalConfig: globalConfigPda })
+      .rpc();
+
+    await program.methods
+      .acceptAdmin()
+      .accounts({
+        newAdmin: newAdmin.publicKey,
+        globalConfig: globalConfigPda,
+      })
+      .signers([newAdmin])
+      .rpc();
+
+    await program.methods
+      .proposeAdmin(admin)
+      .accounts({ admin: newAdmin.publicKey, globalConfig: globalConfigPda })
+      .signers([newAdmin])
+      .rpc();
+
+    await program.methods
+      .acceptAdmin()
+      .accounts({ newAdmin: admin, globalConfig: globalConfigPda })
+      .rpc();
+
+    const config = await program.account.globalConfig.fetch(globalConfigPda);
+    if (!config.admin.equals(admin)) {
+      throw new Error("admin was not handed back");
+    }
+  });
 });